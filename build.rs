@@ -0,0 +1,36 @@
+//! Capture build information for `cargo nav --build-info`.
+
+use std::{env, fs, path::Path, process::Command};
+
+fn main() {
+    let sha = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|s| s.trim().to_owned())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=CARGO_NAV_GIT_SHA={}", sha);
+    println!(
+        "cargo:rustc-env=CARGO_NAV_TARGET={}",
+        env::var("TARGET").unwrap_or_default()
+    );
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    // HEAD usually only names the branch, so also watch where git keeps the
+    // branch's commit: its own ref file, or packed-refs once it's been packed
+    let branch = fs::read_to_string(".git/HEAD").ok().and_then(|head| {
+        head.strip_prefix("ref: ")
+            .map(|r| format!(".git/{}", r.trim()))
+    });
+    let refs = branch.into_iter().chain(
+        [".git/packed-refs", ".git/refs/heads"]
+            .iter()
+            .map(|&path| path.to_owned()),
+    );
+    // a path that doesn't exist would make the script rerun on every build
+    for path in refs.filter(|path| Path::new(path).exists()) {
+        println!("cargo:rerun-if-changed={}", path);
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+}
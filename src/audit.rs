@@ -0,0 +1,190 @@
+//! Link health checks for `cargo nav audit-links`.
+
+use crate::display;
+use anyhow::Result;
+use reqwest::{blocking::Client, StatusCode};
+use serde::Serialize;
+use std::{fmt::Write as _, fs, path::Path, time::Instant};
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// Outcome of requesting one of a crate's links.
+#[derive(Debug, Serialize)]
+pub struct LinkCheck {
+    /// Which link it is, like "homepage".
+    pub kind: String,
+    pub url: String,
+    /// HTTP status of the response, if there was one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// Why the request failed, if it did.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub elapsed_ms: u64,
+}
+
+impl LinkCheck {
+    /// Whether the link answered with a success status (after redirects).
+    pub fn healthy(&self) -> bool {
+        matches!(self.status, Some(s) if s < 400)
+    }
+}
+
+/// Request a link, with a HEAD request or a GET if the server doesn't allow HEAD.
+pub fn check_link(client: &Client, kind: &str, url: &str) -> LinkCheck {
+    let start = Instant::now();
+    let result = client.head(url).send().and_then(|resp| {
+        if resp.status() == StatusCode::METHOD_NOT_ALLOWED {
+            client.get(url).send()
+        } else {
+            Ok(resp)
+        }
+    });
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+    let (status, error) = match result {
+        Ok(resp) => (Some(resp.status().as_u16()), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    LinkCheck {
+        kind: kind.to_owned(),
+        url: url.to_owned(),
+        status,
+        error,
+        elapsed_ms,
+    }
+}
+
+/// Check each `(kind, url)` link in order.
+pub fn check_links(client: &Client, links: &[(&str, &str)]) -> Vec<LinkCheck> {
+    links
+        .iter()
+        .map(|(kind, url)| check_link(client, kind, url))
+        .collect()
+}
+
+/// Report of the checks, a line per link, colored green and red if `color` is set.
+///
+/// Links are shortened for display unless `full_urls` is set.
+pub fn report(checks: &[LinkCheck], color: bool, full_urls: bool) -> String {
+    let mut out = String::new();
+    for check in checks {
+        let outcome = match (&check.status, &check.error) {
+            (Some(status), _) => status.to_string(),
+            (None, Some(e)) => format!("error: {}", e),
+            (None, None) => "no response".to_owned(),
+        };
+        let (mark, paint) = if check.healthy() {
+            ("ok", GREEN)
+        } else {
+            ("BROKEN", RED)
+        };
+        let mark = if color {
+            format!("{}{}{}", paint, mark, RESET)
+        } else {
+            mark.to_owned()
+        };
+        let _ = writeln!(
+            out,
+            "{} {}: {} ({}, {} ms)",
+            mark,
+            check.kind,
+            display::url(&check.url, full_urls),
+            outcome,
+            check.elapsed_ms
+        );
+    }
+    out.trim_end().to_owned()
+}
+
+/// Write the checks to a file as a JSON array.
+pub fn write_json(path: &Path, checks: &[LinkCheck]) -> Result<()> {
+    fs::write(path, serde_json::to_string_pretty(checks)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_link, check_links, report, write_json, LinkCheck};
+    use mockito::mock;
+    use reqwest::blocking::Client;
+    use std::fs;
+
+    fn check(kind: &str, status: Option<u16>, error: Option<&str>) -> LinkCheck {
+        LinkCheck {
+            kind: kind.to_owned(),
+            url: format!("https://example.com/{}", kind),
+            status,
+            error: error.map(str::to_owned),
+            elapsed_ms: 12,
+        }
+    }
+
+    #[test]
+    fn test_check_links() {
+        let _ok = mock("HEAD", "/audit/ok").with_status(200).create();
+        let _gone = mock("HEAD", "/audit/gone").with_status(404).create();
+        let base = mockito::server_url();
+        let ok = format!("{}/audit/ok", base);
+        let gone = format!("{}/audit/gone", base);
+        let checks = check_links(
+            &Client::new(),
+            &[("homepage", ok.as_str()), ("repository", gone.as_str())],
+        );
+        assert_eq!(checks[0].kind, "homepage");
+        assert_eq!(checks[0].status, Some(200));
+        assert!(checks[0].healthy());
+        assert_eq!(checks[1].status, Some(404));
+        assert!(!checks[1].healthy());
+        _ok.assert();
+        _gone.assert();
+    }
+
+    #[test]
+    fn test_check_link_head_not_allowed() {
+        let _head = mock("HEAD", "/audit/get-only").with_status(405).create();
+        let _get = mock("GET", "/audit/get-only").with_status(200).create();
+        let url = format!("{}/audit/get-only", mockito::server_url());
+        let checked = check_link(&Client::new(), "documentation", &url);
+        assert_eq!(checked.status, Some(200));
+        _get.assert();
+    }
+
+    #[test]
+    fn test_check_link_error() {
+        let checked = check_link(&Client::new(), "homepage", "http://127.0.0.1:1/");
+        assert_eq!(checked.status, None);
+        assert!(checked.error.is_some());
+        assert!(!checked.healthy());
+    }
+
+    #[test]
+    fn test_report() {
+        let checks = vec![
+            check("homepage", Some(200), None),
+            check("repository", None, Some("timed out")),
+        ];
+        assert_eq!(
+            report(&checks, false, false),
+            "ok homepage: https://example.com/homepage (200, 12 ms)\n\
+             BROKEN repository: https://example.com/repository (error: timed out, 12 ms)"
+        );
+        let colored = report(&checks, true, false);
+        assert!(colored.starts_with("\x1b[32mok\x1b[0m homepage"));
+        assert!(colored.contains("\x1b[31mBROKEN\x1b[0m repository"));
+    }
+
+    #[test]
+    fn test_write_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.json");
+        write_json(&path, &[check("homepage", Some(301), None)]).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(value[0]["kind"], "homepage");
+        assert_eq!(value[0]["status"], 301);
+        assert_eq!(value[0]["elapsed_ms"], 12);
+        assert!(value[0].get("error").is_none());
+    }
+}
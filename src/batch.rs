@@ -0,0 +1,83 @@
+//! Looking up many crates at once, for `--batch`.
+
+use anyhow::Result;
+use std::{
+    io::BufRead,
+    num::NonZeroUsize,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+/// Crate names listed one per line, skipping blank lines and `#` comments.
+pub fn read_names<R: BufRead>(reader: R) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let name = line.trim();
+        if !name.is_empty() && !name.starts_with('#') {
+            names.push(name.to_owned());
+        }
+    }
+    Ok(names)
+}
+
+/// Number of lookups to run at a time when `--jobs` isn't given.
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+/// Run `fetch` on each item, on up to `jobs` threads at a time, returning the
+/// results in the same order as the items.
+pub fn fetch_all<T: Sync, R: Send>(
+    items: &[T],
+    jobs: usize,
+    fetch: impl Fn(&T) -> R + Sync,
+) -> Vec<R> {
+    let next = AtomicUsize::new(0);
+    let mut results = thread::scope(|scope| {
+        let workers = (0..jobs.clamp(1, items.len().max(1)))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut fetched = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        match items.get(i) {
+                            Some(item) => fetched.push((i, fetch(item))),
+                            None => return fetched,
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+            .collect::<Vec<_>>()
+    });
+    results.sort_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, r)| r).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fetch_all, read_names};
+    use std::{io::Cursor, thread, time::Duration};
+
+    #[test]
+    fn test_read_names() {
+        let names = read_names(Cursor::new("serde\n\n  tokio  \n# later\nrand")).unwrap();
+        assert_eq!(names, vec!["serde", "tokio", "rand"]);
+    }
+
+    #[test]
+    fn test_fetch_all() {
+        let delays = [30, 0, 20, 5, 10];
+        let fetched = fetch_all(&delays, 3, |&ms| {
+            thread::sleep(Duration::from_millis(ms));
+            ms * 2
+        });
+        assert_eq!(fetched, vec![60, 0, 40, 10, 20]);
+        assert_eq!(fetch_all(&delays, 0, |&ms| ms), delays);
+        assert!(fetch_all(&[] as &[u64], 4, |&ms| ms).is_empty());
+    }
+}
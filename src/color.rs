@@ -0,0 +1,117 @@
+//! Deciding whether output is colored, consistently with cargo.
+
+use crate::environment::{self, Environment};
+use structopt::clap::arg_enum;
+
+arg_enum! {
+    /// Value of the `--color` flag.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum ColorChoice {
+        Auto,
+        Always,
+        Never,
+    }
+}
+
+/// Decide whether to color output.
+///
+/// In order: an explicit `--color always` or `never`; `NO_COLOR` being set to
+/// anything non-empty; `CARGO_TERM_COLOR` being `always` or `never`; stdout not
+/// being a terminal; and `TERM=dumb`. Otherwise output is colored.
+pub fn resolve(
+    flag: ColorChoice,
+    no_color: Option<&str>,
+    cargo_term_color: Option<&str>,
+    is_tty: bool,
+    term: Option<&str>,
+) -> bool {
+    match flag {
+        ColorChoice::Always => return true,
+        ColorChoice::Never => return false,
+        ColorChoice::Auto => (),
+    }
+    if no_color.is_some_and(|v| !v.is_empty()) {
+        return false;
+    }
+    match cargo_term_color.and_then(|v| v.parse::<ColorChoice>().ok()) {
+        Some(ColorChoice::Always) => return true,
+        Some(ColorChoice::Never) => return false,
+        _ => (),
+    }
+    is_tty && term != Some("dumb")
+}
+
+/// Decide whether to color output, reading the environment variables from `environment`.
+pub fn use_color(flag: ColorChoice, environment: &Environment, is_tty: bool) -> bool {
+    resolve(
+        flag,
+        environment.var(environment::NO_COLOR).as_deref(),
+        environment.var(environment::CARGO_TERM_COLOR).as_deref(),
+        is_tty,
+        environment.var(environment::TERM).as_deref(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve, use_color, ColorChoice};
+    use crate::environment::Environment;
+
+    #[test]
+    fn test_resolve_matrix() {
+        use ColorChoice::{Always, Auto, Never};
+        type Row<'a> = (
+            ColorChoice,
+            Option<&'a str>,
+            Option<&'a str>,
+            bool,
+            Option<&'a str>,
+            bool,
+        );
+        #[rustfmt::skip]
+        let table: &[Row] = &[
+            // flag, NO_COLOR, CARGO_TERM_COLOR, tty, TERM, colored
+            (Always, Some("1"), Some("never"), false, Some("dumb"), true),
+            (Never, None, Some("always"), true, Some("xterm"), false),
+            (Auto, Some("1"), Some("always"), true, Some("xterm"), false),
+            (Auto, Some(""), None, true, Some("xterm"), true),
+            (Auto, None, Some("always"), false, Some("dumb"), true),
+            (Auto, None, Some("ALWAYS"), false, None, true),
+            (Auto, None, Some("never"), true, Some("xterm"), false),
+            (Auto, None, Some("auto"), true, Some("xterm"), true),
+            (Auto, None, Some("auto"), false, Some("xterm"), false),
+            (Auto, None, Some("sometimes"), true, None, true),
+            (Auto, None, None, true, None, true),
+            (Auto, None, None, false, None, false),
+            (Auto, None, None, true, Some("dumb"), false),
+            (Auto, None, None, false, Some("dumb"), false),
+        ];
+        for (flag, no_color, cargo, tty, term, expected) in table {
+            assert_eq!(
+                resolve(*flag, *no_color, *cargo, *tty, *term),
+                *expected,
+                "flag {:?}, NO_COLOR {:?}, CARGO_TERM_COLOR {:?}, tty {}, TERM {:?}",
+                flag,
+                no_color,
+                cargo,
+                tty,
+                term
+            );
+        }
+    }
+
+    #[test]
+    fn test_use_color_without_environment() {
+        let environment = Environment::new(false);
+        assert!(use_color(ColorChoice::Auto, &environment, true));
+        assert!(!use_color(ColorChoice::Auto, &environment, false));
+        assert!(use_color(ColorChoice::Always, &environment, false));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("never".parse::<ColorChoice>(), Ok(ColorChoice::Never));
+        assert_eq!("Always".parse::<ColorChoice>(), Ok(ColorChoice::Always));
+        assert!("yes".parse::<ColorChoice>().is_err());
+    }
+}
@@ -0,0 +1,55 @@
+//! Putting together cargo commands to paste into a shell.
+
+/// Quote a word for POSIX shells, if it has anything a shell would interpret.
+pub fn quote(word: &str) -> String {
+    let plain = !word.is_empty()
+        && word.chars().all(|c| {
+            c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/' | ':' | '@' | '=' | '+')
+        });
+    if plain {
+        word.to_owned()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
+
+/// A cargo command line, with each argument quoted as needed.
+pub fn cargo(args: &[&str]) -> String {
+    std::iter::once("cargo")
+        .chain(args.iter().copied())
+        .map(quote)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Command to audit the crate for security advisories with cargo-audit.
+pub fn audit(crate_name: &str) -> String {
+    cargo(&["audit", "--package", crate_name])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{audit, cargo, quote};
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("serde_json"), "serde_json");
+        assert_eq!(quote("--package"), "--package");
+        assert_eq!(quote("two words"), "'two words'");
+        assert_eq!(quote("it's"), r"'it'\''s'");
+        assert_eq!(quote("$(rm -rf ~)"), "'$(rm -rf ~)'");
+        assert_eq!(quote(""), "''");
+    }
+
+    #[test]
+    fn test_cargo() {
+        assert_eq!(cargo(&["doc", "--open"]), "cargo doc --open");
+        assert_eq!(cargo(&["add", "a b"]), "cargo add 'a b'");
+    }
+
+    #[test]
+    fn test_audit() {
+        assert_eq!(audit("openssl"), "cargo audit --package openssl");
+        assert_eq!(audit("a;b"), "cargo audit --package 'a;b'");
+    }
+}
@@ -0,0 +1,64 @@
+//! Finding a crate's chat room from the badges and links in its README.
+
+use regex::Regex;
+
+/// Links to Discord invites and Matrix rooms, as used by README badges.
+const CHAT_LINK_PATTERN: &str =
+    r#"https://(?:discord\.gg/|(?:www\.)?discord\.com/invite/|matrix\.to/#/)[^\s)"'<>\]]+"#;
+
+/// The first Discord or Matrix chat link in README text.
+///
+/// Punctuation ending a sentence after a bare link isn't part of it.
+pub fn chat_link(readme: &str) -> Option<String> {
+    let pattern = Regex::new(CHAT_LINK_PATTERN).expect("valid chat link pattern");
+    pattern.find(readme).map(|m| {
+        m.as_str()
+            .trim_end_matches(['.', ',', ';', ':', '!', '?'])
+            .to_owned()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::chat_link;
+
+    #[test]
+    fn test_chat_link_discord_badge() {
+        let readme = r#"
+# tool
+
+[![Crates.io](https://img.shields.io/crates/v/tool.svg)](https://crates.io/crates/tool)
+[![Discord](https://img.shields.io/discord/123456?label=chat)](https://discord.gg/AbCdEf)
+[![Matrix](https://img.shields.io/matrix/tool:matrix.org)](https://matrix.to/#/#tool:matrix.org)
+"#;
+        assert_eq!(
+            chat_link(readme).as_deref(),
+            Some("https://discord.gg/AbCdEf")
+        );
+    }
+
+    #[test]
+    fn test_chat_link_matrix_html() {
+        let readme = r#"<a href="https://matrix.to/#/#tool:matrix.org"><img src="badge.svg"></a>"#;
+        assert_eq!(
+            chat_link(readme).as_deref(),
+            Some("https://matrix.to/#/#tool:matrix.org")
+        );
+    }
+
+    #[test]
+    fn test_chat_link_discord_invite() {
+        assert_eq!(
+            chat_link("Join us at https://discord.com/invite/xyz.").as_deref(),
+            Some("https://discord.com/invite/xyz")
+        );
+    }
+
+    #[test]
+    fn test_chat_link_none() {
+        assert_eq!(
+            chat_link("[![Discord](https://img.shields.io/discord/123)](https://example.com)"),
+            None
+        );
+    }
+}
@@ -0,0 +1,723 @@
+//! User configuration, read from `config.toml` in the cargo-nav config directory,
+//! with any key overridden by a `CARGO_NAV_*` environment variable.
+
+use crate::{environment::Environment, urls};
+use anyhow::{anyhow, Result};
+use log::LevelFilter;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Base URL of the crates.io website.
+pub const DEFAULT_WEB_BASE: &str = "https://crates.io";
+
+/// Prefix of the environment variables that override config keys, like
+/// `CARGO_NAV_WEB_BASE` for `web_base`.
+pub const ENV_PREFIX: &str = "CARGO_NAV_";
+
+/// Other names accepted for config keys, with the key each stands for, in the
+/// config file and as environment variables like `CARGO_NAV_WEB_URL`.
+const KEY_ALIASES: &[(&str, &str)] = &[("web_url", "web_base")];
+
+/// Environment variables read for a config key ahead of its own `CARGO_NAV_`
+/// one, which is then just an alias: `CARGO_NAV_LOG` over `CARGO_NAV_LOG_LEVEL`.
+const KEY_VARIABLES: &[(&str, &str)] = &[("CARGO_NAV_LOG", "log_level")];
+
+/// Where the value of a setting came from, in order of increasing precedence.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Source {
+    Default,
+    File,
+    /// The environment variable with this name.
+    Environment(String),
+    Flag,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::File => write!(f, "config file"),
+            Source::Environment(var) => write!(f, "{}", var),
+            Source::Flag => write!(f, "command line"),
+        }
+    }
+}
+
+/// Settings that apply to a single crate.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CrateOverride {
+    /// Link to a forum thread or mailing list for the crate.
+    pub forum_url: Option<String>,
+}
+
+/// Another registry crates can be published to.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Registry {
+    /// Base URL of the registry's website, with crate pages at `/crates/<name>`.
+    pub web_url: String,
+}
+
+/// Config file contents.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// Per-crate settings, keyed by crate name.
+    pub crate_overrides: HashMap<String, CrateOverride>,
+    /// Destination to use for a crate when none is given, keyed by crate name.
+    pub crate_defaults: HashMap<String, String>,
+    /// Whether to keep a local tally of navigations for `--stats`.
+    pub record_stats: bool,
+    /// Base URL for crate web pages, for using a mirror of the crates.io website.
+    #[serde(alias = "web_url")]
+    pub web_base: Option<String>,
+    /// Template for the URL of a crate's page on an alternative frontend, with
+    /// `{name}` in place of the crate's name.
+    pub frontend: Option<String>,
+    /// Minimum level of log messages to show, like "warn" or "debug".
+    pub log_level: Option<String>,
+    /// Minimum log levels for particular targets, like `reqwest = "warn"`.
+    pub log_targets: BTreeMap<String, String>,
+    /// Command to run before opening each link, which has to succeed for the link
+    /// to be opened. `{url}` is replaced with the link.
+    pub pre_open_cmd: Option<String>,
+    /// Whether to print long output directly instead of through a pager.
+    pub no_pager: bool,
+    /// Registries besides crates.io to open crate pages on with
+    /// `--all-registries`, keyed by name.
+    pub registries: BTreeMap<String, Registry>,
+    /// Where each setting that isn't a default came from, keyed by name.
+    #[serde(skip)]
+    sources: BTreeMap<String, Source>,
+}
+
+impl Config {
+    /// Parse config from TOML text.
+    #[cfg(test)]
+    pub fn from_toml(text: &str) -> Result<Self> {
+        Config::from_sources(Some(text), |_| None)
+    }
+
+    /// Build the config from the config file's text, if there is one, and any
+    /// overriding environment variables, looked up with `var`.
+    ///
+    /// Every key can be overridden, so new keys don't need anything extra. A
+    /// variable's value is read as TOML, like `true` or `{ tokio = "d" }`, or as a
+    /// plain string if it isn't valid TOML.
+    pub fn from_sources(file: Option<&str>, var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let mut table = match file {
+            Some(text) => toml::from_str::<toml::value::Table>(text)
+                .map_err(|e| anyhow!("Invalid config file: {}", e))?,
+            None => toml::value::Table::new(),
+        };
+        for (alias, key) in KEY_ALIASES {
+            if let Some(value) = table.remove(*alias) {
+                table.entry(*key).or_insert(value);
+            }
+        }
+        let mut sources = table
+            .keys()
+            .map(|key| (key.clone(), Source::File))
+            .collect::<BTreeMap<_, _>>();
+        for key in Config::keys() {
+            let preferred = KEY_VARIABLES
+                .iter()
+                .filter(|(_, k)| *k == key)
+                .map(|(name, _)| (*name).to_owned());
+            let aliases = KEY_ALIASES.iter().filter(|(_, k)| *k == key);
+            let names = preferred
+                .chain(std::iter::once(env_var(&key)))
+                .chain(aliases.map(|(a, _)| env_var(a)));
+            // the key's own variable wins over its aliases
+            if let Some((name, value)) = names
+                .filter_map(|name| var(&name).map(|value| (name, value)))
+                .next()
+            {
+                table.insert(key.clone(), env_value(&value));
+                sources.insert(key, Source::Environment(name));
+            }
+        }
+        let mut config =
+            toml::Value::Table(table)
+                .try_into::<Config>()
+                .map_err(|e| match file {
+                    Some(_) if sources.values().all(|s| *s == Source::File) => {
+                        anyhow!("Invalid config file: {}", e)
+                    }
+                    _ => anyhow!("Invalid config: {}", e),
+                })?;
+        config.sources = sources;
+        Ok(config)
+    }
+
+    /// Load the config file, falling back to the defaults if there isn't one, with
+    /// overrides from the environment.
+    pub fn load(environment: &Environment) -> Result<Self> {
+        let var = |name: &str| environment.var(name);
+        let path = match config_path() {
+            Some(p) => p,
+            None => return Config::from_sources(None, var),
+        };
+        match fs::read_to_string(&path) {
+            Ok(text) => Config::from_sources(Some(&text), var),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Config::from_sources(None, var),
+            Err(e) => Err(anyhow!("Could not read {}: {}", path.display(), e)),
+        }
+    }
+
+    /// Names of every config key.
+    pub fn keys() -> Vec<String> {
+        match serde_json::to_value(Config::default()) {
+            Ok(serde_json::Value::Object(map)) => map.keys().cloned().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Value of a setting, like `web_base` or `crate_defaults.tokio`, for
+    /// `config get`. Strings are given without quotes, and unset settings have no value.
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        check_key(key)?;
+        let mut value = serde_json::to_value(self)?;
+        for part in key.split('.') {
+            value = match value.get_mut(part) {
+                Some(v) => v.take(),
+                None => return Ok(None),
+            };
+        }
+        Ok(match value {
+            serde_json::Value::Null => None,
+            serde_json::Value::String(s) => Some(s),
+            other => Some(other.to_string()),
+        })
+    }
+
+    /// Where the setting's value came from.
+    pub fn source(&self, key: &str) -> Source {
+        self.sources.get(key).cloned().unwrap_or(Source::Default)
+    }
+
+    /// Record that a setting was overridden with a command line flag.
+    pub fn set_by_flag(&mut self, key: &str) {
+        self.sources.insert(key.to_owned(), Source::Flag);
+    }
+
+    /// Listing of the config file's location and each setting's value and source.
+    pub fn report(&self, path: Option<&Path>) -> String {
+        let mut lines = vec![match path {
+            Some(p) if p.exists() => format!("Config file: {}", p.display()),
+            Some(p) => format!("Config file: {} (not found)", p.display()),
+            None => String::from("Config file: none (no config directory)"),
+        }];
+        if let Ok(serde_json::Value::Object(values)) = serde_json::to_value(self) {
+            for (key, value) in values {
+                let value = match value {
+                    serde_json::Value::Null => String::from("(not set)"),
+                    other => other.to_string(),
+                };
+                lines.push(format!("{} = {} ({})", key, value, self.source(&key)));
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// Base URL for crate web pages, without a trailing slash.
+    pub fn web_base(&self) -> &str {
+        self.web_base
+            .as_deref()
+            .unwrap_or(DEFAULT_WEB_BASE)
+            .trim_end_matches('/')
+    }
+
+    /// URL of the crate's page, on the configured frontend or the crates.io website.
+    pub fn crate_page(&self, crate_name: &str) -> String {
+        match &self.frontend {
+            Some(template) => template.replace("{name}", &urls::segment(crate_name)),
+            None => urls::path(self.web_base(), &["crates", crate_name]),
+        }
+    }
+
+    /// The crate's page on crates.io (or the configured website) and on each
+    /// configured registry, with the registry's name.
+    pub fn registry_pages(&self, crate_name: &str) -> Vec<(String, String)> {
+        let registries = self.registries.iter().map(|(name, registry)| {
+            let base = registry.web_url.trim_end_matches('/');
+            (name.clone(), urls::path(base, &["crates", crate_name]))
+        });
+        std::iter::once((String::from("crates-io"), self.crate_page(crate_name)))
+            .chain(registries)
+            .collect()
+    }
+
+    /// The configured log level, if there is one.
+    pub fn log_level(&self) -> Result<Option<LevelFilter>> {
+        let setting = match self.source("log_level") {
+            Source::Environment(name) => name,
+            _ => String::from("log_level"),
+        };
+        self.log_level
+            .as_deref()
+            .map(|l| parse_level(l, &setting))
+            .transpose()
+    }
+
+    /// The configured log levels for particular targets.
+    pub fn log_targets(&self) -> Result<Vec<(String, LevelFilter)>> {
+        self.log_targets
+            .iter()
+            .map(|(target, level)| Ok((target.clone(), parse_level(level, target)?)))
+            .collect()
+    }
+
+    /// The crate's default destination, if one was configured.
+    pub fn crate_default(&self, crate_name: &str) -> Option<&str> {
+        self.crate_defaults.get(crate_name).map(String::as_str)
+    }
+
+    /// Settings for the crate, if any were configured.
+    pub fn crate_override(&self, crate_name: &str) -> Option<&CrateOverride> {
+        self.crate_overrides.get(crate_name)
+    }
+}
+
+/// Parse a log level from the config, naming the setting it's for in errors.
+fn parse_level(level: &str, setting: &str) -> Result<LevelFilter> {
+    level
+        .parse()
+        .map_err(|_| anyhow!("Invalid log level '{}' for {}", level, setting))
+}
+
+/// Make sure the setting, like `web_base` or `crate_defaults.tokio`, starts with a
+/// config key, listing the valid ones if it doesn't.
+fn check_key(key: &str) -> Result<()> {
+    let keys = Config::keys();
+    let top = key.split('.').next().unwrap_or_default();
+    if keys.iter().any(|k| k == top) && !key.split('.').any(str::is_empty) {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Unknown config key '{}'; the keys are: {}",
+            key,
+            keys.join(", ")
+        ))
+    }
+}
+
+/// Set a setting in the config file for `config set`, creating the file if needed.
+///
+/// The value is read as TOML, or as a string if it isn't valid TOML, like
+/// environment variable overrides. The file is only written if the new config
+/// is valid. Comments in the file aren't kept.
+pub fn set(path: &Path, key: &str, value: &str) -> Result<()> {
+    check_key(key)?;
+    let mut table = match fs::read_to_string(path) {
+        Ok(text) => toml::from_str::<toml::value::Table>(&text)
+            .map_err(|e| anyhow!("Invalid config file: {}", e))?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => toml::value::Table::new(),
+        Err(e) => return Err(anyhow!("Could not read {}: {}", path.display(), e)),
+    };
+    let mut parts = key.split('.').collect::<Vec<_>>();
+    let last = parts.pop().unwrap_or_default();
+    let mut current = &mut table;
+    for part in parts {
+        current = match current
+            .entry(part.to_owned())
+            .or_insert_with(|| toml::Value::Table(toml::value::Table::new()))
+        {
+            toml::Value::Table(t) => t,
+            _ => return Err(anyhow!("'{}' in '{}' isn't a table", part, key)),
+        };
+    }
+    current.insert(last.to_owned(), env_value(value));
+    let table = toml::Value::Table(table);
+    table
+        .clone()
+        .try_into::<Config>()
+        .map_err(|e| anyhow!("Invalid value for {}: {}", key, e))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, toml::to_string(&table)?)?;
+    Ok(())
+}
+
+/// Delete the config file for `config reset`, returning whether there was one.
+pub fn reset(path: &Path) -> Result<bool> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(anyhow!("Could not delete {}: {}", path.display(), e)),
+    }
+}
+
+/// Name of the environment variable that overrides a config key.
+pub fn env_var(key: &str) -> String {
+    format!("{}{}", ENV_PREFIX, key.to_uppercase())
+}
+
+/// Read an environment variable's value as a TOML value, or else a string.
+fn env_value(value: &str) -> toml::Value {
+    toml::from_str::<toml::value::Table>(&format!("value = {}", value))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(value.to_owned()))
+}
+
+/// Location of the config file.
+pub fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("cargo-nav").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{env_var, reset, set, Config, Source};
+    use log::LevelFilter;
+    use std::fs;
+
+    /// Look up variables from a list instead of the environment.
+    fn vars<'a>(list: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            list.iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| (*v).to_owned())
+        }
+    }
+
+    #[test]
+    fn test_from_toml_empty() {
+        let config = Config::from_toml("").unwrap();
+        assert!(config.crate_overrides.is_empty());
+        assert!(!config.record_stats);
+        assert_eq!(config.web_base(), "https://crates.io");
+    }
+
+    #[test]
+    fn test_from_toml_crate_overrides() {
+        let config = Config::from_toml(
+            r#"
+[crate_overrides.tokio]
+forum_url = "https://users.rust-lang.org/c/tokio"
+"#,
+        )
+        .unwrap();
+        let tokio = config.crate_override("tokio").unwrap();
+        assert_eq!(
+            tokio.forum_url.as_deref(),
+            Some("https://users.rust-lang.org/c/tokio")
+        );
+        assert!(config.crate_override("serde").is_none());
+    }
+
+    #[test]
+    fn test_from_toml_crate_defaults() {
+        let config = Config::from_toml(
+            r#"
+[crate_defaults]
+tokio = "d"
+clap = "r"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.crate_default("tokio"), Some("d"));
+        assert_eq!(config.crate_default("clap"), Some("r"));
+        assert_eq!(config.crate_default("serde"), None);
+    }
+
+    #[test]
+    fn test_from_toml_record_stats() {
+        let config = Config::from_toml("record_stats = true").unwrap();
+        assert!(config.record_stats);
+    }
+
+    #[test]
+    fn test_from_toml_web_base() {
+        let config = Config::from_toml(r#"web_base = "https://crates.example.com/""#).unwrap();
+        assert_eq!(config.web_base(), "https://crates.example.com");
+        let config = Config::from_toml(r#"web_url = "https://crates.example.com//""#).unwrap();
+        assert_eq!(config.web_base(), "https://crates.example.com");
+    }
+
+    #[test]
+    fn test_from_toml_log_levels() {
+        let config = Config::from_toml(
+            r#"
+log_level = "warn"
+
+[log_targets]
+reqwest = "warn"
+"cargo_nav::audit" = "TRACE"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.log_level().unwrap(), Some(LevelFilter::Warn));
+        assert_eq!(
+            config.log_targets().unwrap(),
+            vec![
+                ("cargo_nav::audit".to_owned(), LevelFilter::Trace),
+                ("reqwest".to_owned(), LevelFilter::Warn),
+            ]
+        );
+        let empty = Config::from_toml("").unwrap();
+        assert_eq!(empty.log_level().unwrap(), None);
+        assert!(empty.log_targets().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_from_toml_log_levels_invalid() {
+        let config = Config::from_toml("[log_targets]\nreqwest = \"loud\"").unwrap();
+        assert_eq!(
+            config.log_targets().unwrap_err().to_string(),
+            "Invalid log level 'loud' for reqwest"
+        );
+        let config = Config::from_toml(r#"log_level = "loud""#).unwrap();
+        assert!(config.log_level().is_err());
+    }
+
+    #[test]
+    fn test_from_toml_pre_open_cmd() {
+        let config = Config::from_toml(r#"pre_open_cmd = "url-allowed {url}""#).unwrap();
+        assert_eq!(config.pre_open_cmd.as_deref(), Some("url-allowed {url}"));
+        assert!(Config::from_toml("").unwrap().pre_open_cmd.is_none());
+    }
+
+    #[test]
+    fn test_from_toml_no_pager() {
+        assert!(Config::from_toml("no_pager = true").unwrap().no_pager);
+        assert!(!Config::from_toml("").unwrap().no_pager);
+    }
+
+    #[test]
+    fn test_crate_page() {
+        let config = Config::from_toml("").unwrap();
+        assert_eq!(config.crate_page("serde"), "https://crates.io/crates/serde");
+        let config = Config::from_toml(r#"web_base = "https://mirror.example.com""#).unwrap();
+        assert_eq!(
+            config.crate_page("serde"),
+            "https://mirror.example.com/crates/serde"
+        );
+        let config = Config::from_toml(
+            r#"
+web_base = "https://mirror.example.com"
+frontend = "https://lib.rs/crates/{name}"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.crate_page("serde"), "https://lib.rs/crates/serde");
+    }
+
+    #[test]
+    fn test_registry_pages() {
+        let config = Config::from_toml("").unwrap();
+        assert_eq!(
+            config.registry_pages("serde"),
+            vec![(
+                "crates-io".to_owned(),
+                "https://crates.io/crates/serde".to_owned()
+            )]
+        );
+        let config = Config::from_toml(
+            r#"
+[registries.internal]
+web_url = "https://crates.internal.example.com/"
+
+[registries.mirror]
+web_url = "https://mirror.example.com"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.registry_pages("serde"),
+            vec![
+                (
+                    "crates-io".to_owned(),
+                    "https://crates.io/crates/serde".to_owned()
+                ),
+                (
+                    "internal".to_owned(),
+                    "https://crates.internal.example.com/crates/serde".to_owned()
+                ),
+                (
+                    "mirror".to_owned(),
+                    "https://mirror.example.com/crates/serde".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_toml_invalid() {
+        assert!(Config::from_toml("crate_overrides = 1").is_err());
+    }
+
+    #[test]
+    fn test_env_var() {
+        assert_eq!(env_var("web_base"), "CARGO_NAV_WEB_BASE");
+        let keys = Config::keys();
+        assert!(keys.contains(&"pre_open_cmd".to_owned()));
+        assert!(keys.contains(&"crate_defaults".to_owned()));
+        assert!(!keys.contains(&"sources".to_owned()));
+    }
+
+    #[test]
+    fn test_precedence() {
+        let file = r#"
+web_base = "https://file.example.com"
+record_stats = true
+pre_open_cmd = "from-file {url}"
+"#;
+        let env = [
+            ("CARGO_NAV_WEB_BASE", "https://env.example.com"),
+            ("CARGO_NAV_NO_PAGER", "true"),
+            ("CARGO_NAV_PRE_OPEN_CMD", "from-env {url}"),
+            ("CARGO_NAV_LOG", "debug"),
+        ];
+        let mut config = Config::from_sources(Some(file), vars(&env)).unwrap();
+        // environment over file
+        assert_eq!(config.web_base(), "https://env.example.com");
+        assert_eq!(
+            config.source("web_base"),
+            Source::Environment("CARGO_NAV_WEB_BASE".to_owned())
+        );
+        // environment over default
+        assert!(config.no_pager);
+        // file over default
+        assert!(config.record_stats);
+        assert_eq!(config.source("record_stats"), Source::File);
+        // default
+        assert!(config.frontend.is_none());
+        assert_eq!(config.source("frontend"), Source::Default);
+        // flag over environment
+        config.pre_open_cmd = Some("from-flag {url}".to_owned());
+        config.set_by_flag("pre_open_cmd");
+        assert_eq!(config.source("pre_open_cmd"), Source::Flag);
+    }
+
+    #[test]
+    fn test_web_url_alias() {
+        let file = r#"web_url = "https://file.example.com""#;
+        let config = Config::from_sources(Some(file), vars(&[])).unwrap();
+        assert_eq!(config.web_base(), "https://file.example.com");
+        assert_eq!(config.source("web_base"), Source::File);
+
+        let env = [("CARGO_NAV_WEB_URL", "https://env.example.com/")];
+        let config = Config::from_sources(Some(file), vars(&env)).unwrap();
+        assert_eq!(config.web_base(), "https://env.example.com");
+        assert_eq!(
+            config.source("web_base"),
+            Source::Environment("CARGO_NAV_WEB_URL".to_owned())
+        );
+        assert_eq!(
+            config.crate_page("serde"),
+            "https://env.example.com/crates/serde"
+        );
+
+        let env = [
+            ("CARGO_NAV_WEB_URL", "https://alias.example.com"),
+            ("CARGO_NAV_WEB_BASE", "https://key.example.com"),
+        ];
+        let config = Config::from_sources(None, vars(&env)).unwrap();
+        assert_eq!(config.web_base(), "https://key.example.com");
+    }
+
+    #[test]
+    fn test_env_values() {
+        let env = [
+            ("CARGO_NAV_CRATE_DEFAULTS", r#"{ tokio = "d" }"#),
+            ("CARGO_NAV_FRONTEND", "https://lib.rs/crates/{name}"),
+            ("CARGO_NAV_LOG_LEVEL", "warn"),
+        ];
+        let config = Config::from_sources(None, vars(&env)).unwrap();
+        assert_eq!(config.crate_default("tokio"), Some("d"));
+        assert_eq!(config.crate_page("serde"), "https://lib.rs/crates/serde");
+        assert_eq!(config.log_level().unwrap(), Some(LevelFilter::Warn));
+
+        let err =
+            Config::from_sources(None, vars(&[("CARGO_NAV_RECORD_STATS", "often")])).unwrap_err();
+        assert!(err.to_string().starts_with("Invalid config: "));
+    }
+
+    #[test]
+    fn test_log_level_variables() {
+        let file = r#"log_level = "error""#;
+        let config =
+            Config::from_sources(Some(file), vars(&[("CARGO_NAV_LOG_LEVEL", "warn")])).unwrap();
+        assert_eq!(config.log_level().unwrap(), Some(LevelFilter::Warn));
+
+        let env = [("CARGO_NAV_LOG_LEVEL", "warn"), ("CARGO_NAV_LOG", "debug")];
+        let config = Config::from_sources(Some(file), vars(&env)).unwrap();
+        assert_eq!(config.log_level().unwrap(), Some(LevelFilter::Debug));
+        assert_eq!(
+            config.source("log_level"),
+            Source::Environment("CARGO_NAV_LOG".to_owned())
+        );
+
+        let config = Config::from_sources(None, vars(&[("CARGO_NAV_LOG", "loud")])).unwrap();
+        assert_eq!(
+            config.log_level().unwrap_err().to_string(),
+            "Invalid log level 'loud' for CARGO_NAV_LOG"
+        );
+    }
+
+    #[test]
+    fn test_set_get_reset() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cargo-nav").join("config.toml");
+        set(&path, "web_base", "https://mirror.example.com").unwrap();
+        set(&path, "record_stats", "true").unwrap();
+        set(&path, "crate_defaults.tokio", "d").unwrap();
+        set(&path, "web_base", "https://crates.example.com").unwrap();
+        let config = Config::from_toml(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(config.web_base(), "https://crates.example.com");
+        assert!(config.record_stats);
+        assert_eq!(config.crate_default("tokio"), Some("d"));
+
+        assert_eq!(
+            config.get("web_base").unwrap().as_deref(),
+            Some("https://crates.example.com")
+        );
+        assert_eq!(config.get("record_stats").unwrap().as_deref(), Some("true"));
+        assert_eq!(
+            config.get("crate_defaults.tokio").unwrap().as_deref(),
+            Some("d")
+        );
+        assert_eq!(config.get("crate_defaults.clap").unwrap(), None);
+        assert_eq!(config.get("frontend").unwrap(), None);
+
+        assert!(reset(&path).unwrap());
+        assert!(!path.exists());
+        assert!(!reset(&path).unwrap());
+    }
+
+    #[test]
+    fn test_set_invalid() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let err = set(&path, "default_destination", "documentation").unwrap_err();
+        assert!(err.to_string().starts_with(
+            "Unknown config key 'default_destination'; the keys are: crate_defaults, "
+        ));
+        assert!(Config::default().get("nope").is_err());
+        assert!(set(&path, "web_base.", "x").is_err());
+        assert!(set(&path, "record_stats", "often").is_err());
+        assert!(set(&path, "web_base.inner", "x").is_err());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_report() {
+        let config = Config::from_sources(
+            Some("no_pager = true"),
+            vars(&[("CARGO_NAV_WEB_BASE", "x")]),
+        )
+        .unwrap();
+        let report = config.report(None);
+        assert!(report.starts_with("Config file: none"));
+        assert!(report.contains("\nno_pager = true (config file)\n"));
+        assert!(report.contains("\nweb_base = \"x\" (CARGO_NAV_WEB_BASE)"));
+        assert!(report.contains("\nfrontend = (not set) (default)\n"));
+    }
+}
@@ -0,0 +1,81 @@
+//! Spotting crates that say they're deprecated, for `--deprecated-check`.
+
+use regex::Regex;
+
+/// Phrases in a description saying not to use the crate anymore.
+const DEPRECATION_PATTERN: &str = r"(?i)\b(?:deprecated|unmaintained|no longer (?:maintained|supported|developed)|not (?:actively )?maintained|superseded by\s+`?[\w-]+`?|(?:use|try|switch to)\s+`?[\w-]+`?\s+instead)\b";
+
+/// Keywords crates are tagged with when they're deprecated.
+const DEPRECATION_KEYWORDS: &[&str] = &["deprecated", "unmaintained"];
+
+/// What marks the crate as deprecated, from its description or keywords, if anything.
+pub fn marker(description: Option<&str>, keywords: &[String]) -> Option<String> {
+    let pattern = Regex::new(DEPRECATION_PATTERN).expect("valid deprecation pattern");
+    if let Some(found) = description.and_then(|d| pattern.find(d)) {
+        return Some(
+            found
+                .as_str()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+    }
+    keywords
+        .iter()
+        .find(|k| DEPRECATION_KEYWORDS.contains(&k.to_lowercase().as_str()))
+        .map(|k| format!("keyword {}", k))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::marker;
+
+    #[test]
+    fn test_marker_description() {
+        let cases = [
+            ("DEPRECATED: this crate is no longer updated", "DEPRECATED"),
+            ("A JSON parser (unmaintained)", "unmaintained"),
+            (
+                "No longer maintained, see the README",
+                "No longer maintained",
+            ),
+            (
+                "Old bindings. Use `new-bindings` instead.",
+                "Use `new-bindings` instead",
+            ),
+            ("Superseded by\n tokio", "Superseded by tokio"),
+            ("Use serde_json instead", "Use serde_json instead"),
+        ];
+        for (description, expected) in &cases {
+            assert_eq!(
+                marker(Some(description), &[]).as_deref(),
+                Some(*expected),
+                "{}",
+                description
+            );
+        }
+    }
+
+    #[test]
+    fn test_marker_keywords() {
+        let keywords = vec!["parser".to_owned(), "Deprecated".to_owned()];
+        assert_eq!(
+            marker(Some("A parser"), &keywords).as_deref(),
+            Some("keyword Deprecated")
+        );
+        assert_eq!(marker(None, &keywords[..1]), None);
+    }
+
+    #[test]
+    fn test_marker_none() {
+        let descriptions = [
+            "A generic serialization/deserialization framework",
+            "Use it to parse things, instead of writing a parser",
+            "Maintained by the async working group",
+        ];
+        for description in &descriptions {
+            assert_eq!(marker(Some(description), &[]), None, "{}", description);
+        }
+        assert_eq!(marker(None, &[]), None);
+    }
+}
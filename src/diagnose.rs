@@ -0,0 +1,89 @@
+//! Connectivity checks for narrowing down why the crates.io API couldn't be reached.
+
+use std::{
+    fmt,
+    net::{SocketAddr, TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+/// How long to wait on each TCP connection attempt.
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Outcome of the connectivity checks against a host.
+#[derive(Debug)]
+pub enum Diagnosis {
+    /// The host name couldn't be resolved.
+    Dns(String),
+    /// The host name resolved, but no address accepted a TCP connection.
+    Tcp(Vec<SocketAddr>, String),
+    /// A TCP connection was made, so the problem is further up (TLS, proxy, HTTP).
+    Reachable(SocketAddr),
+}
+
+impl fmt::Display for Diagnosis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Diagnosis::Dns(e) => write!(f, "DNS lookup failed: {}", e),
+            Diagnosis::Tcp(addrs, e) => {
+                let addrs = addrs
+                    .iter()
+                    .map(|a| a.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(
+                    f,
+                    "DNS lookup succeeded ({}), but TCP connect failed: {}",
+                    addrs, e
+                )
+            }
+            Diagnosis::Reachable(addr) => write!(
+                f,
+                "DNS lookup and TCP connect to {} succeeded; the failure is likely TLS or a proxy",
+                addr
+            ),
+        }
+    }
+}
+
+/// Check, in order, name resolution and TCP connectivity to the host.
+pub fn diagnose(host: &str, port: u16, timeout: Duration) -> Diagnosis {
+    let addrs = match (host, port).to_socket_addrs() {
+        Ok(a) => a.collect::<Vec<_>>(),
+        Err(e) => return Diagnosis::Dns(e.to_string()),
+    };
+    if addrs.is_empty() {
+        return Diagnosis::Dns(format!("no addresses found for {}", host));
+    }
+    let mut last_error = String::new();
+    for addr in &addrs {
+        match TcpStream::connect_timeout(addr, timeout) {
+            Ok(_) => return Diagnosis::Reachable(*addr),
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+    Diagnosis::Tcp(addrs, last_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diagnose, Diagnosis, CONNECT_TIMEOUT};
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_diagnose_reachable() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let result = diagnose("127.0.0.1", port, CONNECT_TIMEOUT);
+        assert!(matches!(result, Diagnosis::Reachable(_)));
+    }
+
+    #[test]
+    fn test_diagnose_tcp_failure() {
+        let port = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let result = diagnose("127.0.0.1", port, CONNECT_TIMEOUT);
+        assert!(matches!(result, Diagnosis::Tcp(_, _)));
+    }
+}
@@ -0,0 +1,126 @@
+//! Sending output to a Unix domain socket or named pipe, for tools that watch
+//! for navigation events.
+
+use anyhow::{anyhow, Result};
+use std::path::Path;
+
+/// Write a line of text to the socket or FIFO at the path.
+///
+/// Nothing waits for the other end: if no process is listening on the socket
+/// or reading from the FIFO, an error is returned straight away.
+#[cfg(unix)]
+pub fn emit(path: &Path, text: &str) -> Result<()> {
+    use std::{
+        fs::{self, OpenOptions},
+        io::Write,
+        os::unix::{
+            fs::{FileTypeExt, OpenOptionsExt},
+            net::UnixStream,
+        },
+    };
+
+    let file_type = fs::metadata(path)
+        .map_err(|e| anyhow!("Could not find {}: {}", path.display(), e))?
+        .file_type();
+    let line = format!("{}\n", text);
+    if file_type.is_socket() {
+        let mut stream = UnixStream::connect(path)
+            .map_err(|e| anyhow!("Nothing is listening on {}: {}", path.display(), e))?;
+        stream.write_all(line.as_bytes())?;
+    } else if file_type.is_fifo() {
+        let mut fifo = OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(path)
+            .map_err(|e| anyhow!("Nothing is reading from {}: {}", path.display(), e))?;
+        fifo.write_all(line.as_bytes())?;
+    } else {
+        return Err(anyhow!(
+            "{} isn't a Unix socket or named pipe",
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Write a line of text to the socket or FIFO at the path.
+#[cfg(not(unix))]
+pub fn emit(_path: &Path, _text: &str) -> Result<()> {
+    Err(anyhow!("--emit-to is only supported on Unix"))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::emit;
+    use std::{
+        ffi::CString,
+        fs::{self, OpenOptions},
+        io::{BufRead, BufReader, Read},
+        os::unix::{ffi::OsStrExt, fs::OpenOptionsExt, net::UnixListener},
+        path::Path,
+        thread,
+    };
+
+    #[test]
+    fn test_emit_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nav.sock");
+        let listener = UnixListener::bind(&path).unwrap();
+        let reader = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut line = String::new();
+            BufReader::new(stream).read_line(&mut line).unwrap();
+            line
+        });
+        emit(&path, r#"{"name":"a"}"#).unwrap();
+        assert_eq!(reader.join().unwrap(), "{\"name\":\"a\"}\n");
+    }
+
+    #[test]
+    fn test_emit_socket_not_listening() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nav.sock");
+        drop(UnixListener::bind(&path).unwrap());
+        let err = emit(&path, "{}").unwrap_err();
+        assert!(err.to_string().starts_with("Nothing is listening on"));
+    }
+
+    fn make_fifo(path: &Path) {
+        let c_path = CString::new(path.as_os_str().as_bytes()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) }, 0);
+    }
+
+    #[test]
+    fn test_emit_fifo() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nav.fifo");
+        make_fifo(&path);
+        let mut reader = OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(&path)
+            .unwrap();
+        emit(&path, "{}").unwrap();
+        let mut text = String::new();
+        reader.read_to_string(&mut text).unwrap();
+        assert_eq!(text, "{}\n");
+    }
+
+    #[test]
+    fn test_emit_fifo_no_reader() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nav.fifo");
+        make_fifo(&path);
+        let err = emit(&path, "{}").unwrap_err();
+        assert!(err.to_string().starts_with("Nothing is reading from"));
+    }
+
+    #[test]
+    fn test_emit_regular_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.json");
+        fs::write(&path, "").unwrap();
+        assert!(emit(&path, "{}").is_err());
+        assert!(emit(&dir.path().join("missing"), "{}").is_err());
+    }
+}
@@ -0,0 +1,82 @@
+//! Environment variables cargo-nav takes input from, read in one place so
+//! `--no-env` can turn them all off.
+
+use std::{env, ffi::OsString};
+
+/// Token for the GitHub API, used when `--github-token` isn't given.
+pub const GITHUB_TOKEN: &str = "GITHUB_TOKEN";
+/// Where cargo puts build output, including generated docs.
+pub const CARGO_TARGET_DIR: &str = "CARGO_TARGET_DIR";
+/// Turns off colored output when set to anything non-empty.
+pub const NO_COLOR: &str = "NO_COLOR";
+/// Cargo's color setting: auto, always, or never.
+pub const CARGO_TERM_COLOR: &str = "CARGO_TERM_COLOR";
+/// Terminal type; "dumb" terminals don't get colored output.
+pub const TERM: &str = "TERM";
+/// Command to page long output through.
+pub const PAGER: &str = "PAGER";
+
+/// Source of environment variable input.
+#[derive(Clone, Copy, Debug)]
+pub struct Environment {
+    enabled: bool,
+}
+
+impl Environment {
+    /// Read from the process environment, or nothing at all if not `enabled`.
+    pub fn new(enabled: bool) -> Self {
+        Environment { enabled }
+    }
+
+    /// Whether environment variables are read.
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Value of a variable, if it's set to valid Unicode and reading is enabled.
+    pub fn var(&self, name: &str) -> Option<String> {
+        self.var_os(name).and_then(|v| v.into_string().ok())
+    }
+
+    /// Value of a variable, if it's set and reading is enabled.
+    pub fn var_os(&self, name: &str) -> Option<OsString> {
+        if self.enabled {
+            env::var_os(name)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Environment;
+    use std::env;
+
+    #[test]
+    fn test_var() {
+        env::set_var("CARGO_NAV_TEST_ENVIRONMENT_VAR", "set");
+        assert_eq!(
+            Environment::new(true)
+                .var("CARGO_NAV_TEST_ENVIRONMENT_VAR")
+                .as_deref(),
+            Some("set")
+        );
+        assert_eq!(
+            Environment::new(true).var("CARGO_NAV_TEST_ENVIRONMENT_UNSET"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_var_disabled() {
+        env::set_var("CARGO_NAV_TEST_ENVIRONMENT_DISABLED", "set");
+        let environment = Environment::new(false);
+        assert!(!environment.enabled());
+        assert_eq!(environment.var("CARGO_NAV_TEST_ENVIRONMENT_DISABLED"), None);
+        assert_eq!(
+            environment.var_os("CARGO_NAV_TEST_ENVIRONMENT_DISABLED"),
+            None
+        );
+    }
+}
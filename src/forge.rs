@@ -0,0 +1,485 @@
+//! Helpers for working with repository URLs on code hosting sites.
+
+use crate::urls;
+use anyhow::{anyhow, Result};
+
+/// Known code hosting sites, which differ in their URL layouts.
+#[derive(Debug, PartialEq)]
+pub enum Forge {
+    GitHub,
+    GitLab,
+    Other,
+}
+
+/// Determine which site hosts the repository.
+pub fn forge(repo_url: &str) -> Forge {
+    let host = repo_url
+        .split("://")
+        .nth(1)
+        .and_then(|rest| rest.split('/').next())
+        .unwrap_or("");
+    match host.trim_start_matches("www.") {
+        "github.com" => Forge::GitHub,
+        "gitlab.com" => Forge::GitLab,
+        _ => Forge::Other,
+    }
+}
+
+/// Turn a git remote URL into the URL of the repository's web page.
+///
+/// Handles `git+` prefixes, scp-style `git@host:owner/repo` remotes,
+/// `ssh://` and `git://` schemes, and trailing `.git` suffixes.
+pub fn normalize_repo_url(url: &str) -> String {
+    let url = url.trim().trim_start_matches("git+");
+    let url = if let Some(rest) = url.strip_prefix("ssh://") {
+        format!("https://{}", rest.trim_start_matches("git@"))
+    } else if let Some(rest) = url.strip_prefix("git://") {
+        format!("https://{}", rest)
+    } else if let Some(rest) = url.strip_prefix("git@") {
+        format!("https://{}", rest.replacen(':', "/", 1))
+    } else {
+        url.to_owned()
+    };
+    url.trim_end_matches('/')
+        .trim_end_matches(".git")
+        .to_owned()
+}
+
+/// Owner and repository name from a GitHub or GitLab repository URL.
+///
+/// Anything past the repository name, like a `/tree/main/subcrate` path, is ignored.
+pub fn owner_repo(repo_url: &str) -> Option<(String, String)> {
+    let repo_url = normalize_repo_url(repo_url);
+    if forge(&repo_url) == Forge::Other {
+        return None;
+    }
+    let mut segments = repo_url
+        .split("://")
+        .nth(1)?
+        .split('/')
+        .skip(1)
+        .filter(|s| !s.is_empty());
+    let owner = segments.next()?.to_owned();
+    let repo = segments.next()?.trim_end_matches(".git").to_owned();
+    Some((owner, repo))
+}
+
+/// Owner and repository name from a GitHub repository URL.
+pub fn github_owner_repo(repo_url: &str) -> Result<(String, String)> {
+    match owner_repo(repo_url) {
+        Some(pair) if forge(&normalize_repo_url(repo_url)) == Forge::GitHub => Ok(pair),
+        _ => Err(anyhow!("{} isn't a GitHub repository", repo_url)),
+    }
+}
+
+/// Name of the project a repository URL points at.
+///
+/// For a monorepo subpath like `.../tree/main/tokio-util` that's the last directory
+/// of the subpath, otherwise it's the repository's name.
+pub fn project_name(repo_url: &str) -> Option<String> {
+    let url = normalize_repo_url(repo_url);
+    let segments = url
+        .split("://")
+        .nth(1)?
+        .split('/')
+        .skip(1)
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+    let subpath = segments
+        .iter()
+        .position(|s| *s == "tree" || *s == "blob")
+        .filter(|i| segments.len() > i + 2);
+    if subpath.is_some() {
+        return segments.last().map(|s| s.to_string());
+    }
+    owner_repo(&url)
+        .map(|(_, repo)| repo)
+        .or_else(|| segments.last().map(|s| s.to_string()))
+}
+
+/// Prefixes and suffixes often added to a project's name to make a crate or
+/// repository name.
+const NAME_AFFIXES: &[&str] = &["rust-", "rs-", "-rs", "-rust", "-lib", "-crate"];
+
+/// Whether a crate's name is plausibly the name of the project its repository
+/// points at.
+///
+/// Hyphens, underscores, and dots are treated alike, affixes like `-rs` are
+/// ignored, and a crate named after its project plus a suffix (like `tokio-util`
+/// in the `tokio` repository) is accepted.
+pub fn names_match(crate_name: &str, project: &str) -> bool {
+    let normalize = |name: &str| {
+        let mut name = name.to_lowercase().replace(['_', '.'], "-");
+        for affix in NAME_AFFIXES {
+            if let Some(rest) = name
+                .strip_prefix(affix)
+                .or_else(|| name.strip_suffix(affix))
+            {
+                if !rest.is_empty() {
+                    name = rest.to_owned();
+                }
+            }
+        }
+        name
+    };
+    let (crate_name, project) = (normalize(crate_name), normalize(project));
+    crate_name == project
+        || crate_name.starts_with(&format!("{}-", project))
+        || crate_name.ends_with(&format!("-{}", project))
+        || project.starts_with(&format!("{}-", crate_name))
+}
+
+/// URL of a page under a GitHub repository, like its issues or security tab.
+pub fn github_url(repo_url: &str, page: &str) -> Result<String> {
+    let (owner, repo) = github_owner_repo(repo_url)?;
+    Ok(format!("https://github.com/{}/{}/{}", owner, repo, page))
+}
+
+/// URL of the repository's file tree at a given branch, tag, or commit.
+pub fn tree_url(repo_url: &str, reference: &str) -> String {
+    let repo_url = normalize_repo_url(repo_url);
+    match forge(&repo_url) {
+        Forge::GitLab => format!("{}/-/tree/{}", repo_url, reference),
+        Forge::GitHub | Forge::Other => format!("{}/tree/{}", repo_url, reference),
+    }
+}
+
+/// Tag a release is expected to have.
+///
+/// Most crates tag releases as `v1.2.3`, so a bare version gets a `v` prefix.
+fn release_tag(version: &str) -> String {
+    if version.starts_with('v') {
+        version.to_owned()
+    } else {
+        format!("v{}", version)
+    }
+}
+
+/// URL of a GitHub or GitLab repository's file tree at a release's tag.
+pub fn repo_tree_url(repo_url: &str, version: &str) -> Result<String> {
+    let tag = urls::segment(&release_tag(version));
+    match forge(&normalize_repo_url(repo_url)) {
+        Forge::GitHub | Forge::GitLab => Ok(tree_url(repo_url, &tag)),
+        Forge::Other => Err(anyhow!(
+            "Only GitHub and GitLab repositories are supported, not {}",
+            repo_url
+        )),
+    }
+}
+
+/// URL of a GitHub or GitLab page comparing two releases, or a release and `HEAD`
+/// when there's no second version.
+pub fn compare_url(repo_url: &str, from: &str, to: Option<&str>) -> Result<String> {
+    let repo = normalize_repo_url(repo_url);
+    let range = format!(
+        "{}...{}",
+        urls::segment(&release_tag(from)),
+        to.map(|t| urls::segment(&release_tag(t)))
+            .unwrap_or_else(|| String::from("HEAD"))
+    );
+    match forge(&repo) {
+        Forge::GitHub => Ok(format!("{}/compare/{}", repo, range)),
+        Forge::GitLab => Ok(format!("{}/-/compare/{}", repo, range)),
+        Forge::Other => Err(anyhow!(
+            "Only GitHub and GitLab repositories are supported, not {}",
+            repo_url
+        )),
+    }
+}
+
+/// URL of the repository's code coverage report: Codecov for GitHub, Coveralls
+/// for GitLab.
+pub fn coverage_url(repo_url: &str) -> Result<String> {
+    let repo = normalize_repo_url(repo_url);
+    match (forge(&repo), owner_repo(&repo)) {
+        (Forge::GitHub, Some((owner, name))) => {
+            Ok(format!("https://codecov.io/gh/{}/{}", owner, name))
+        }
+        (Forge::GitLab, Some((owner, name))) => {
+            Ok(format!("https://coveralls.io/gitlab/{}/{}", owner, name))
+        }
+        _ => Err(anyhow!(
+            "Only GitHub and GitLab repositories are supported, not {}",
+            repo_url
+        )),
+    }
+}
+
+/// URL of the container image published with a GitHub repository, on the GitHub
+/// Container Registry (ghcr.io), assuming it's named after the repository.
+pub fn container_registry_url(repo_url: &str) -> Result<String> {
+    let (owner, repo) = github_owner_repo(repo_url)?;
+    Ok(format!(
+        "https://github.com/{}/{}/pkgs/container/{}",
+        owner, repo, repo
+    ))
+}
+
+/// URL of the repository on Sourcegraph, for searching its code, if it's on
+/// GitHub or GitLab.
+pub fn sourcegraph_url(repo_url: &str) -> Option<String> {
+    let repo = normalize_repo_url(repo_url);
+    let host = match forge(&repo) {
+        Forge::GitHub => "github.com",
+        Forge::GitLab => "gitlab.com",
+        Forge::Other => return None,
+    };
+    let (owner, name) = owner_repo(&repo)?;
+    Some(format!(
+        "https://sourcegraph.com/{}/{}/{}",
+        host, owner, name
+    ))
+}
+
+/// URL of the repository's GitHub Discussions.
+///
+/// GitLab doesn't have discussions, so its issues, newest first, are used instead.
+pub fn discussions_url(repo_url: &str) -> Result<String> {
+    let repo = normalize_repo_url(repo_url);
+    match (forge(&repo), owner_repo(&repo)) {
+        (Forge::GitHub, Some((owner, name))) => {
+            Ok(format!("https://github.com/{}/{}/discussions", owner, name))
+        }
+        (Forge::GitLab, Some((owner, name))) => Ok(format!(
+            "https://gitlab.com/{}/{}/-/issues?scope=all&sort=created_date",
+            owner, name
+        )),
+        _ => Err(anyhow!(
+            "Only GitHub and GitLab repositories are supported, not {}",
+            repo_url
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        compare_url, container_registry_url, coverage_url, discussions_url, forge,
+        github_owner_repo, github_url, names_match, normalize_repo_url, owner_repo, project_name,
+        repo_tree_url, sourcegraph_url, tree_url, Forge,
+    };
+
+    #[test]
+    fn test_forge() {
+        assert_eq!(forge("https://github.com/serde-rs/serde"), Forge::GitHub);
+        assert_eq!(
+            forge("https://www.github.com/serde-rs/serde"),
+            Forge::GitHub
+        );
+        assert_eq!(forge("https://gitlab.com/a/b"), Forge::GitLab);
+        assert_eq!(forge("https://git.sr.ht/~a/b"), Forge::Other);
+        assert_eq!(forge("not a url"), Forge::Other);
+    }
+
+    #[test]
+    fn test_normalize_repo_url() {
+        let expected = "https://github.com/serde-rs/serde";
+        assert_eq!(
+            normalize_repo_url("https://github.com/serde-rs/serde"),
+            expected
+        );
+        assert_eq!(
+            normalize_repo_url("https://github.com/serde-rs/serde.git"),
+            expected
+        );
+        assert_eq!(
+            normalize_repo_url("https://github.com/serde-rs/serde/"),
+            expected
+        );
+        assert_eq!(
+            normalize_repo_url("git+https://github.com/serde-rs/serde"),
+            expected
+        );
+        assert_eq!(
+            normalize_repo_url("git@github.com:serde-rs/serde.git"),
+            expected
+        );
+        assert_eq!(
+            normalize_repo_url("ssh://git@github.com/serde-rs/serde"),
+            expected
+        );
+        assert_eq!(
+            normalize_repo_url("git://github.com/serde-rs/serde"),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_tree_url() {
+        assert_eq!(
+            tree_url("https://github.com/a/b.git", "main"),
+            "https://github.com/a/b/tree/main"
+        );
+        assert_eq!(
+            tree_url("https://gitlab.com/a/b", "v1.0.0"),
+            "https://gitlab.com/a/b/-/tree/v1.0.0"
+        );
+    }
+
+    #[test]
+    fn test_repo_tree_url_github() {
+        assert_eq!(
+            repo_tree_url("https://github.com/serde-rs/serde", "1.0.0").unwrap(),
+            "https://github.com/serde-rs/serde/tree/v1.0.0"
+        );
+        assert_eq!(
+            repo_tree_url("https://github.com/serde-rs/serde.git", "v1.0.0").unwrap(),
+            "https://github.com/serde-rs/serde/tree/v1.0.0"
+        );
+    }
+
+    #[test]
+    fn test_repo_tree_url_gitlab() {
+        assert_eq!(
+            repo_tree_url("https://gitlab.com/a/b", "0.2.1").unwrap(),
+            "https://gitlab.com/a/b/-/tree/v0.2.1"
+        );
+        assert_eq!(
+            repo_tree_url("https://gitlab.com/a/b", "v0.2.1").unwrap(),
+            "https://gitlab.com/a/b/-/tree/v0.2.1"
+        );
+    }
+
+    #[test]
+    fn test_repo_tree_url_other() {
+        assert!(repo_tree_url("https://git.sr.ht/~a/b", "1.0.0").is_err());
+    }
+
+    #[test]
+    fn test_compare_url() {
+        assert_eq!(
+            compare_url("https://github.com/a/b.git", "1.0.0", Some("v1.0.1")).unwrap(),
+            "https://github.com/a/b/compare/v1.0.0...v1.0.1"
+        );
+        assert_eq!(
+            compare_url("https://github.com/a/b", "1.0.0", None).unwrap(),
+            "https://github.com/a/b/compare/v1.0.0...HEAD"
+        );
+        assert_eq!(
+            compare_url("https://gitlab.com/a/b", "0.1.0", Some("0.2.0")).unwrap(),
+            "https://gitlab.com/a/b/-/compare/v0.1.0...v0.2.0"
+        );
+        assert!(compare_url("https://git.sr.ht/~a/b", "1.0.0", None).is_err());
+    }
+
+    #[test]
+    fn test_project_name() {
+        assert_eq!(
+            project_name("https://github.com/serde-rs/serde.git").as_deref(),
+            Some("serde")
+        );
+        assert_eq!(
+            project_name("https://github.com/tokio-rs/tokio/tree/master/tokio-util").as_deref(),
+            Some("tokio-util")
+        );
+        assert_eq!(
+            project_name("https://gitlab.com/a/b/-/tree/main/crates/c").as_deref(),
+            Some("c")
+        );
+        assert_eq!(
+            project_name("https://git.sr.ht/~a/tool").as_deref(),
+            Some("tool")
+        );
+        assert_eq!(project_name("https://example.com"), None);
+    }
+
+    #[test]
+    fn test_names_match() {
+        assert!(names_match("serde", "serde"));
+        assert!(names_match("serde_json", "json"));
+        assert!(names_match("async_std", "async-std"));
+        assert!(names_match("ring", "ring-rs"));
+        assert!(names_match("regex", "rust-regex"));
+        assert!(names_match("tokio-util", "tokio"));
+        assert!(names_match("Inflector", "inflector"));
+        assert!(!names_match("sered", "serde"));
+        assert!(!names_match("fastrand", "tokio"));
+        assert!(!names_match("rs", "tool"));
+    }
+
+    #[test]
+    fn test_coverage_url() {
+        assert_eq!(
+            coverage_url("https://github.com/serde-rs/serde.git").unwrap(),
+            "https://codecov.io/gh/serde-rs/serde"
+        );
+        assert_eq!(
+            coverage_url("https://gitlab.com/a/b").unwrap(),
+            "https://coveralls.io/gitlab/a/b"
+        );
+        assert!(coverage_url("https://git.sr.ht/~a/b").is_err());
+    }
+
+    #[test]
+    fn test_discussions_url() {
+        assert_eq!(
+            discussions_url("https://github.com/tokio-rs/tokio/tree/master/tokio-util").unwrap(),
+            "https://github.com/tokio-rs/tokio/discussions"
+        );
+        assert_eq!(
+            discussions_url("git@gitlab.com:a/b.git").unwrap(),
+            "https://gitlab.com/a/b/-/issues?scope=all&sort=created_date"
+        );
+        assert!(discussions_url("https://git.sr.ht/~a/b").is_err());
+    }
+
+    #[test]
+    fn test_container_registry_url() {
+        assert_eq!(
+            container_registry_url("https://github.com/LukeMathWalker/cargo-chef.git").unwrap(),
+            "https://github.com/LukeMathWalker/cargo-chef/pkgs/container/cargo-chef"
+        );
+        assert!(container_registry_url("https://gitlab.com/a/b").is_err());
+    }
+
+    #[test]
+    fn test_sourcegraph_url() {
+        assert_eq!(
+            sourcegraph_url("https://github.com/tokio-rs/tokio/tree/master/tokio-util").as_deref(),
+            Some("https://sourcegraph.com/github.com/tokio-rs/tokio")
+        );
+        assert_eq!(
+            sourcegraph_url("git@gitlab.com:a/b.git").as_deref(),
+            Some("https://sourcegraph.com/gitlab.com/a/b")
+        );
+        assert_eq!(sourcegraph_url("https://git.sr.ht/~a/b"), None);
+    }
+
+    #[test]
+    fn test_owner_repo() {
+        let expected = Some(("tokio-rs".to_owned(), "tokio".to_owned()));
+        assert_eq!(owner_repo("https://github.com/tokio-rs/tokio"), expected);
+        assert_eq!(
+            owner_repo("https://github.com/tokio-rs/tokio.git"),
+            expected
+        );
+        assert_eq!(
+            owner_repo("https://github.com/tokio-rs/tokio/tree/master/tokio-util"),
+            expected
+        );
+        assert_eq!(owner_repo("https://github.com/tokio-rs"), None);
+        assert_eq!(owner_repo("https://git.sr.ht/~a/b"), None);
+    }
+
+    #[test]
+    fn test_github_owner_repo() {
+        assert_eq!(
+            github_owner_repo("git@github.com:a/b.git").unwrap(),
+            ("a".to_owned(), "b".to_owned())
+        );
+        assert!(github_owner_repo("https://gitlab.com/a/b").is_err());
+    }
+
+    #[test]
+    fn test_github_url() {
+        assert_eq!(
+            github_url("https://github.com/serde-rs/serde", "security").unwrap(),
+            "https://github.com/serde-rs/serde/security"
+        );
+        assert_eq!(
+            github_url("git@github.com:serde-rs/serde.git", "issues").unwrap(),
+            "https://github.com/serde-rs/serde/issues"
+        );
+        assert!(github_url("https://gitlab.com/a/b", "security").is_err());
+    }
+}
@@ -0,0 +1,82 @@
+//! The pre-open hook: a command run before each link is opened, which can veto it.
+
+use anyhow::{anyhow, Result};
+use std::process::Command;
+
+/// Placeholder in the hook command that's replaced with the link.
+pub const URL_PLACEHOLDER: &str = "{url}";
+
+/// Split the hook command into the program and its arguments, filling in the link.
+///
+/// The command is split on whitespace and run directly, not through a shell, so
+/// the link can't be interpreted as anything but an argument.
+pub fn hook_args(template: &str, url: &str) -> Vec<String> {
+    template
+        .split_whitespace()
+        .map(|word| word.replace(URL_PLACEHOLDER, url))
+        .collect()
+}
+
+/// Run the hook for the link, returning whether it allows opening it.
+pub fn allows(template: &str, url: &str) -> Result<bool> {
+    let args = hook_args(template, url);
+    let (program, rest) = args
+        .split_first()
+        .ok_or_else(|| anyhow!("The pre-open command is empty"))?;
+    let status = Command::new(program)
+        .args(rest)
+        .status()
+        .map_err(|e| anyhow!("Could not run the pre-open command {}: {}", program, e))?;
+    Ok(status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{allows, hook_args};
+
+    #[test]
+    fn test_hook_args() {
+        assert_eq!(
+            hook_args("log-url --tag nav {url}", "https://docs.rs/serde"),
+            vec!["log-url", "--tag", "nav", "https://docs.rs/serde"]
+        );
+        assert_eq!(
+            hook_args("check --link={url}", "https://a.b/c d"),
+            vec!["check", "--link=https://a.b/c d"]
+        );
+        assert!(hook_args("  ", "u").is_empty());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_allows() {
+        assert!(allows("true {url}", "https://docs.rs/serde").unwrap());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_blocks() {
+        assert!(!allows("false {url}", "https://docs.rs/serde").unwrap());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_sees_url() {
+        assert!(allows(
+            "test {url} = https://docs.rs/serde",
+            "https://docs.rs/serde"
+        )
+        .unwrap());
+        assert!(!allows(
+            "test {url} = https://docs.rs/serde",
+            "https://evil.example.com"
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(allows("", "u").is_err());
+        assert!(allows("cargo-nav-no-such-hook-command {url}", "u").is_err());
+    }
+}
@@ -0,0 +1,115 @@
+//! The all-in-one page: a local HTML file linking to every destination for a crate.
+
+use anyhow::Result;
+use std::{env, fs, path::PathBuf};
+
+/// Escape text for including in HTML, in element content or quoted attributes.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// HTML page listing the crate's links, each with the name of its destination.
+pub fn render(crate_name: &str, links: &[(&str, String)]) -> String {
+    let items = links
+        .iter()
+        .map(|(label, url)| {
+            format!(
+                "      <li><span>{}</span><a href=\"{}\">{}</a></li>\n",
+                escape(label),
+                escape(url),
+                escape(url)
+            )
+        })
+        .collect::<String>();
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+  <head>
+    <meta charset="utf-8">
+    <title>{name} - cargo-nav</title>
+    <style>
+      body {{ font-family: system-ui, sans-serif; max-width: 48rem; margin: 3rem auto; padding: 0 1rem; color: #222; }}
+      ul {{ list-style: none; padding: 0; }}
+      li {{ display: flex; gap: 1rem; padding: 0.4rem 0; border-bottom: 1px solid #eee; }}
+      span {{ min-width: 10rem; color: #666; }}
+      a {{ color: #0b63c4; text-decoration: none; overflow-wrap: anywhere; }}
+      a:hover {{ text-decoration: underline; }}
+    </style>
+  </head>
+  <body>
+    <h1>{name}</h1>
+    <ul>
+{items}    </ul>
+  </body>
+</html>
+"#,
+        name = escape(crate_name),
+        items = items
+    )
+}
+
+/// Name of the page's file, made unique with a timestamp.
+pub fn file_name(crate_name: &str, timestamp: u64) -> String {
+    format!("cargo-nav-{}-{}.html", crate_name, timestamp)
+}
+
+/// Write the page to the temporary directory, returning its path.
+pub fn write(crate_name: &str, links: &[(&str, String)], timestamp: u64) -> Result<PathBuf> {
+    let path = env::temp_dir().join(file_name(crate_name, timestamp));
+    fs::write(&path, render(crate_name, links))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape, file_name, render, write};
+    use std::fs;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape(r#"<a href="x">Tom & Jerry's</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+        );
+        assert_eq!(escape("plain"), "plain");
+    }
+
+    #[test]
+    fn test_render() {
+        let links = [
+            ("documentation", "https://docs.rs/serde".to_owned()),
+            ("forum", "https://example.com/?a=1&b=2".to_owned()),
+        ];
+        let html = render("serde", &links);
+        assert!(html.contains("<title>serde - cargo-nav</title>"));
+        assert!(html.contains(
+            r#"<li><span>documentation</span><a href="https://docs.rs/serde">https://docs.rs/serde</a></li>"#
+        ));
+        assert!(html.contains(r#"href="https://example.com/?a=1&amp;b=2""#));
+    }
+
+    #[test]
+    fn test_write() {
+        assert_eq!(
+            file_name("serde", 1700000000),
+            "cargo-nav-serde-1700000000.html"
+        );
+        let path = write("cargo-nav-html-test", &[], 1).unwrap();
+        assert!(path.ends_with("cargo-nav-cargo-nav-html-test-1.html"));
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains("<h1>cargo-nav-html-test</h1>"));
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -5,15 +5,76 @@
 
 #![deny(clippy::all)]
 
+mod audit;
+mod batch;
+mod color;
+mod command;
+mod community;
+mod config;
+mod deprecation;
+mod diagnose;
+mod display;
+mod emit;
+mod environment;
+mod forge;
+mod hook;
+mod html;
+mod manifest;
+mod opener;
+mod output;
+mod pager;
+mod prompt;
+mod readme;
+mod renames;
+mod resolver;
+mod serve;
+mod session;
+mod stats;
+mod timings;
+mod trace;
+mod urls;
+
 use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate};
+use color::ColorChoice;
+use config::Config;
+use environment::Environment;
 use fern::{
     colors::{Color, ColoredLevelConfig},
     Dispatch,
 };
-use log::{debug, error, info, LevelFilter};
-use serde::Deserialize;
-use std::{env, fmt, io, process};
-use structopt::{clap::arg_enum, StructOpt};
+use log::{debug, error, info, warn, LevelFilter};
+use manifest::{Manifest, Patch, PatchSource};
+use opener::Opener;
+use output::{PipeWriter, Terminator};
+use pager::Pager;
+use prompt::Prompt;
+use reqwest::{
+    blocking::Client,
+    header::{HeaderMap, HeaderName, HeaderValue, CONTENT_LENGTH, LOCATION},
+    redirect::Policy,
+    tls, StatusCode,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use session::SessionRecord;
+use std::{
+    cell::OnceCell,
+    collections::{BTreeMap, HashMap},
+    env, error, fmt, fs,
+    fs::File,
+    io::{self, BufRead},
+    net::TcpListener,
+    path::{Path, PathBuf},
+    process, thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use structopt::{
+    clap::{arg_enum, AppSettings, Error as ClapError, ErrorKind},
+    StructOpt,
+};
+use timings::Timings;
+use trace::Trace;
 
 arg_enum! {
     /// Destination options.
@@ -26,251 +87,6915 @@ arg_enum! {
         H, Homepage,
         D, Documentation,
         R, Repository,
+        F, Forum,
+        FeatureFlags,
+        Settings,
+        Security,
+        Advisory,
+        PullRequests,
+        Team,
+        Compare,
+        Versions,
+        Coverage,
+        OwnerProfile,
+        SimilarCrates,
+        ArchivedVersion,
+        Archive,
+        Discussions,
+        ContainerRegistry,
+        Sourcegraph,
+        HomepageOrDocs,
+        RawIndex,
+        ReproducibleBuilds,
+    }
+}
+
+arg_enum! {
+    /// Orders crates can be listed in, for `--order`.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum CrateOrder {
+        Name,
+        Downloads,
+        Updated,
+    }
+}
+
+arg_enum! {
+    /// Themes docs.rs can show documentation in, for `--theme`.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum DocsTheme {
+        Light,
+        Dark,
+        Ayu,
+    }
+}
+
+impl Destination {
+    /// Full, lowercase name of the destination.
+    fn label(&self) -> &'static str {
+        match self {
+            Destination::C | Destination::Crate => "crate",
+            Destination::H | Destination::Homepage => "homepage",
+            Destination::D | Destination::Documentation => "documentation",
+            Destination::R | Destination::Repository => "repository",
+            Destination::F | Destination::Forum => "forum",
+            Destination::FeatureFlags => "feature flags",
+            Destination::Settings => "settings",
+            Destination::Security => "security",
+            Destination::Advisory => "advisory",
+            Destination::PullRequests => "pull requests",
+            Destination::Team => "team",
+            Destination::Compare => "compare",
+            Destination::Versions => "versions",
+            Destination::Coverage => "coverage",
+            Destination::OwnerProfile => "owner profile",
+            Destination::SimilarCrates => "similar crates",
+            Destination::ArchivedVersion => "archived version",
+            Destination::Archive => "archive",
+            Destination::Discussions => "discussions",
+            Destination::ContainerRegistry => "containerregistry",
+            Destination::Sourcegraph => "sourcegraph",
+            Destination::HomepageOrDocs => "homepage or docs",
+            Destination::RawIndex => "raw index",
+            Destination::ReproducibleBuilds => "reproducible builds",
+        }
+    }
+
+    /// Whether the link is built from the crate's repository.
+    fn uses_repository(&self) -> bool {
+        matches!(
+            self,
+            Destination::R
+                | Destination::Repository
+                | Destination::Security
+                | Destination::PullRequests
+                | Destination::Compare
+                | Destination::Coverage
+                | Destination::Discussions
+                | Destination::ContainerRegistry
+                | Destination::Sourcegraph
+        )
+    }
+
+    /// Whether the crate's owners need to be fetched to find the link.
+    fn needs_owners(&self) -> bool {
+        matches!(self, Destination::Team | Destination::OwnerProfile)
+    }
+}
+
+/// Split a `name@version` crate argument, combining the version with `--crate-version`.
+///
+/// Both parts have to be non-empty, the name can only have the characters crates.io
+/// allows, and the version has to start with a digit. Giving different versions in
+/// the argument and the flag is an error.
+fn split_crate_version(arg: &str, flag: Option<String>) -> Result<(String, Option<String>)> {
+    let (name, version) = match arg.split_once('@') {
+        Some((name, version)) => (name, Some(version)),
+        None => (arg, None),
+    };
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow!("'{}' isn't a valid crate name", name));
+    }
+    let version = match version {
+        Some(v) if v.starts_with(|c: char| c.is_ascii_digit()) && !v.contains('@') => v,
+        Some(v) => return Err(anyhow!("'{}' isn't a valid version for {}", v, name)),
+        None => return Ok((name.to_owned(), flag)),
+    };
+    match flag {
+        Some(f) if f != version => Err(anyhow!(
+            "The crate is given as {}@{} but --crate-version is {}",
+            name,
+            version,
+            f
+        )),
+        _ => Ok((name.to_owned(), Some(version.to_owned()))),
+    }
+}
+
+/// Check that a version given on the command line is a full semver version, like
+/// `1.2.3` or `1.0.0-rc.1`, for `--strict-semver`.
+fn check_strict_version(version: &str) -> Result<()> {
+    semver::Version::parse(version)
+        .map(|_| ())
+        .map_err(|e| anyhow!("'{}' isn't a full semver version: {}", version, e))
+}
+
+/// Fields `--print-json-field` can print.
+const JSON_FIELDS: &[&str] = &[
+    "name",
+    "homepage",
+    "documentation",
+    "repository",
+    "updated_at",
+    "downloads",
+    "recent_downloads",
+    "crate",
+];
+
+/// Other names people commonly use for destinations.
+const DESTINATION_ALIASES: &[(&str, &str)] = &[
+    ("docs", "documentation"),
+    ("ghcr", "containerregistry"),
+    ("index", "rawindex"),
+];
+
+/// Parse a destination given by name, shorthand letter, alias, or unambiguous prefix.
+fn parse_destination(s: &str) -> Result<Destination> {
+    if let Ok(d) = s.parse::<Destination>() {
+        return Ok(d);
+    }
+    let names = Destination::variants()
+        .iter()
+        .filter(|v| v.len() > 1)
+        .map(|v| v.to_lowercase())
+        .collect::<Vec<_>>();
+    let names = names.iter().map(String::as_str).collect::<Vec<_>>();
+    match_name(s, &names, DESTINATION_ALIASES)?
+        .parse::<Destination>()
+        .map_err(|e| anyhow!(e))
+}
+
+/// Find which of the names the input means.
+///
+/// Matching is case-insensitive, and accepts a whole name, an alias, or a prefix
+/// of exactly one name. Otherwise the error lists the names a prefix could be,
+/// or suggests the closest name by edit distance.
+fn match_name<'a>(input: &str, names: &[&'a str], aliases: &[(&str, &'a str)]) -> Result<&'a str> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return Err(anyhow!("No destination given"));
+    }
+    if let Some(name) = names.iter().find(|n| n.to_lowercase() == input) {
+        return Ok(*name);
+    }
+    if let Some((_, name)) = aliases.iter().find(|(alias, _)| *alias == input) {
+        return Ok(*name);
     }
+    let candidates = names
+        .iter()
+        .filter(|n| n.to_lowercase().starts_with(&input))
+        .copied()
+        .collect::<Vec<_>>();
+    match candidates.as_slice() {
+        [name] => Ok(*name),
+        [] => {
+            let closest = names
+                .iter()
+                .map(|n| (edit_distance(&input, &n.to_lowercase()), n))
+                .min_by_key(|(distance, _)| *distance);
+            match closest {
+                Some((distance, name)) if distance <= (name.len() / 2).max(2) => Err(anyhow!(
+                    "Unknown destination '{}'; did you mean `{}`?",
+                    input,
+                    name
+                )),
+                _ => Err(anyhow!(
+                    "Unknown destination '{}'; expected one of: {}",
+                    input,
+                    names.join(", ")
+                )),
+            }
+        }
+        _ => Err(anyhow!(
+            "Destination '{}' is ambiguous; it could be: {}",
+            input,
+            candidates.join(", ")
+        )),
+    }
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Homepage patterns that indicate the homepage is a forum or community thread.
+const FORUM_PATTERNS: &[&str] = &["users.rust-lang.org", "reddit.com/r/rust"];
+
+/// Subcommands, used instead of a crate name.
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Explain step by step how a crate's link is resolved, without opening it
+    Resolve {
+        crate_name: String,
+
+        #[structopt(parse(try_from_str = parse_destination))]
+        destination: Option<Destination>,
+    },
+    /// Check that the crate's homepage, documentation, and repository links
+    /// respond, exiting with an error if any are broken
+    AuditLinks {
+        crate_name: String,
+
+        /// Also write the results to this file as JSON
+        #[structopt(long, parse(from_os_str))]
+        output: Option<PathBuf>,
+    },
+    /// Read or change settings in the config file
+    Config(ConfigCommand),
+    /// List the crates published by a crates.io user
+    Author {
+        login: String,
+
+        /// Open the crates.io page of the crate with this number in the list
+        #[structopt(long)]
+        open: Option<usize>,
+    },
+    /// Print the crates you navigate to most, from the local stats file
+    Stats {
+        /// How many crates to list
+        #[structopt(long, default_value = "10")]
+        limit: usize,
+    },
+}
+
+/// Subcommands of `config`.
+#[derive(Debug, StructOpt)]
+enum ConfigCommand {
+    /// Change a setting in the config file, like `web_base https://crates.io`
+    /// or `crate_defaults.tokio d`
+    Set { key: String, value: String },
+    /// Print the value of a setting, including any environment variable override
+    Get { key: String },
+    /// Delete the config file
+    Reset,
 }
 
 #[derive(Debug, StructOpt)]
-#[structopt(name = "cargo-nav")]
+#[structopt(name = "cargo-nav", setting = AppSettings::DisableHelpSubcommand)]
 /// CLI program for quickly navigating to crate links as found on crates.io.
 ///
 /// Call with: cargo nav <crate-name> [destination]
 ///
 /// The 'destination' argument is one of several options, shown below. The single-
-/// letter versions are shorthand for less typing.
+/// letter versions are shorthand for less typing, and any unambiguous start of a
+/// name works too, like 'doc' or 'repo'. 'featureflags' opens the crate on
+/// featureflags.dev, and 'reproduciblebuilds' on reproducible.rs; they're
+/// third-party services that may not cover every crate.
 struct Options {
+    /// When to color output: auto, always, or never. With auto, NO_COLOR and
+    /// CARGO_TERM_COLOR are respected, and output is only colored on a terminal
+    #[structopt(
+        long,
+        default_value = "auto",
+        possible_values = &ColorChoice::variants(),
+        case_insensitive = true
+    )]
+    color: ColorChoice,
+
+    /// Answer every prompt with its default instead of asking, for
+    /// non-interactive use
+    #[structopt(short = "y", long)]
+    assume_yes: bool,
+
+    /// Show debug logging; the same as --log-level debug
     #[structopt(short, long)]
     debug: bool,
 
-    crate_name: String,
+    /// Minimum level of log messages to show. Defaults to the CARGO_NAV_LOG (or
+    /// CARGO_NAV_LOG_LEVEL) environment variable, then the config file, then info
+    #[structopt(
+        long,
+        possible_values = &["error", "warn", "info", "debug", "trace"],
+        case_insensitive = true,
+        conflicts_with = "debug"
+    )]
+    log_level: Option<LevelFilter>,
+
+    /// Don't print cautionary notices, like a repository that seems to belong
+    /// to a different project
+    #[structopt(short, long)]
+    quiet: bool,
+
+    /// On network failure, check DNS and TCP connectivity to the API host
+    #[structopt(long)]
+    diagnose: bool,
+
+    /// Print the JSON crates.io returns for the crate, pretty-printed, and exit
+    #[structopt(long)]
+    raw_response: bool,
+
+    /// Don't fall back to the crate page when no destination is given; ask
+    /// which link to open instead (or fail when not run interactively)
+    #[structopt(long)]
+    no_default_crate_page: bool,
+
+    /// Print version, git commit, and target information, then exit
+    #[structopt(long)]
+    build_info: bool,
+
+    /// Print where the config file is and where each setting came from, then exit
+    #[structopt(long)]
+    paths: bool,
+
+    /// Copy a Markdown link to the crate's destination to the clipboard and print
+    /// it, instead of opening it
+    #[structopt(long)]
+    copy_markdown: bool,
+
+    /// Only look for the crate among the current project's git dependencies,
+    /// skipping crates.io
+    #[structopt(long)]
+    project_only: bool,
+
+    /// Print the crate's links and the chosen link as JSON instead of opening it.
+    /// Lookup failures are reported in the JSON with a zero exit code
+    #[structopt(long)]
+    json: bool,
+
+    /// Write the --json output to this Unix socket or named pipe instead of
+    /// stdout (Unix only)
+    #[structopt(long, parse(from_os_str))]
+    emit_to: Option<PathBuf>,
+
+    /// With --json, print a JSON array of results even for a single crate
+    #[structopt(long)]
+    format_json_array: bool,
+
+    /// Print only this field of the crate's info, or nothing if it isn't set.
+    /// 'crate' is the crate's page on crates.io
+    #[structopt(long, possible_values = JSON_FIELDS)]
+    print_json_field: Option<String>,
+
+    /// Version of the crate to use for version-specific links. The crate can also be
+    /// given as 'name@version'; giving a different version both ways is an error
+    #[structopt(long)]
+    crate_version: Option<String>,
+
+    /// Reject versions that aren't full semver versions, like '1.0' for 1.0.0,
+    /// instead of leaving them for the registry to resolve
+    #[structopt(long)]
+    strict_semver: bool,
+
+    /// Features to look for in the documentation, comma-separated. Links to
+    /// the docs.rs features page and shows what optional dependencies they enable
+    #[structopt(long, use_delimiter = true)]
+    features: Vec<String>,
+
+    /// Open the chat room (Discord or Matrix) linked from the README of the
+    /// crate's GitHub repository, instead of a destination
+    #[structopt(long)]
+    community: bool,
+
+    /// When the homepage is asked for but not set, look for one in the README
+    /// of the crate's GitHub repository
+    #[structopt(long)]
+    infer_homepage: bool,
+
+    /// For the compare destination, the older version to compare
+    #[structopt(long)]
+    from_version: Option<String>,
+
+    /// For the compare destination, the newer version to compare (defaults to
+    /// the latest commit)
+    #[structopt(long)]
+    to_version: Option<String>,
+
+    /// For the versions destination, how many recent versions to list
+    #[structopt(long, default_value = "10")]
+    limit: usize,
+
+    /// Also go to the same destination for the crate's dependencies, this many
+    /// levels deep
+    #[structopt(long, default_value = "0")]
+    depth: usize,
+
+    /// With --depth, open at most this many links, counting the crate's own
+    #[structopt(long, default_value = "10")]
+    max_tabs: usize,
+
+    /// Keep checking the crate every this many seconds, printing when its newest
+    /// version changes, until interrupted with Ctrl-C
+    #[structopt(long)]
+    watch: Option<u64>,
+
+    /// Open the repository's source tree at the tag for --crate-version
+    #[structopt(long)]
+    open_repo_tree_at_version: bool,
+
+    /// Always use the latest version, skipping the requests that only look up
+    /// versions, like listing them for the versions destination and --du
+    #[structopt(long)]
+    no_version_check: bool,
+
+    /// Skip optional network lookups, like the download trend for --info and
+    /// --json
+    #[structopt(long)]
+    offline: bool,
+
+    /// Print the crate's links, with the size and publish date of the version,
+    /// instead of opening anything
+    #[structopt(long)]
+    info: bool,
+
+    /// With --info, also ask the download server for the size of the `.crate` file
+    #[structopt(long)]
+    du: bool,
+
+    /// With --info, cut the description off at this many characters (defaults
+    /// to the terminal's width on a terminal)
+    #[structopt(long)]
+    desc_width: Option<usize>,
+
+    /// Base URL of the crates.io website, or a mirror of it, for crate pages.
+    /// Overrides web_base in the config
+    #[structopt(long)]
+    web_url: Option<String>,
+
+    /// With the archive destination, save the `.crate` file to the current
+    /// directory instead of opening it
+    #[structopt(long)]
+    download: bool,
+
+    /// Don't send long output, like --info and the versions listing, through a pager
+    #[structopt(long)]
+    no_pager: bool,
+
+    /// When the requested link isn't set, list the links that are and exit
+    /// successfully instead of failing
+    #[structopt(long)]
+    no_open_on_missing: bool,
+
+    /// Only continue if the crate was updated on or after this date (YYYY-MM-DD);
+    /// otherwise exit successfully without opening anything
+    #[structopt(long)]
+    open_if_updated_after: Option<NaiveDate>,
+
+    /// With --open-if-updated-after, print "skipped: not updated" when skipping
+    #[structopt(long)]
+    quiet_skip: bool,
+
+    /// Print the link (or local path) instead of opening it
+    #[structopt(long)]
+    print: bool,
+
+    /// Like --print, but end each printed link with a NUL byte instead of a
+    /// newline, for `xargs -0`
+    #[structopt(long)]
+    print0: bool,
+
+    /// Instead of a single destination, open one page linking to all of them
+    #[structopt(long)]
+    all_in_one: bool,
+
+    /// Print a `cargo audit` command for the crate, ready to paste. With the
+    /// advisory destination the RustSec page is opened too; otherwise that's all
+    #[structopt(long)]
+    open_audit_command: bool,
+
+    /// Show docs.rs pages in this theme: light, dark, or ayu
+    #[structopt(
+        long,
+        possible_values = &DocsTheme::variants(),
+        case_insensitive = true
+    )]
+    theme: Option<DocsTheme>,
+
+    /// Open the crate's page on crates.io and on each registry in the config's
+    /// [registries] table
+    #[structopt(long)]
+    all_registries: bool,
+
+    /// Show links in full in messages and listings, instead of shortened
+    #[structopt(long)]
+    full_urls: bool,
+
+    /// Always look the crate up on crates.io, even if the current project has
+    /// it as a path dependency, and with the name exactly as given rather than
+    /// lowercased
+    #[structopt(long)]
+    exact: bool,
+
+    /// Warn if the crate's description or keywords say it's deprecated or
+    /// unmaintained
+    #[structopt(long)]
+    deprecated_check: bool,
+
+    /// Print how long each phase of the run took to stderr
+    #[structopt(long, hidden = true)]
+    timings: bool,
+
+    /// Use the repository link exactly as published, without checking whether
+    /// the repository has been renamed or moved
+    #[structopt(long)]
+    no_follow_renames: bool,
+
+    /// For a path dependency's documentation, run `cargo doc --open` instead of
+    /// printing the command
+    #[structopt(long)]
+    build_docs: bool,
+
+    /// For the documentation destination, only open the docs if docs.rs built
+    /// them successfully
+    #[structopt(long)]
+    only_if_documented: bool,
+
+    /// For the documentation destination, open the docs built locally by
+    /// `cargo doc` instead of the crate's documentation link
+    #[structopt(long)]
+    local_docs: bool,
+
+    /// DANGER: accept invalid TLS certificates, e.g. from an intercepting corporate
+    /// proxy. Only ever settable here, never from the config file
+    #[structopt(long)]
+    ignore_tls_errors: bool,
+
+    /// Extra header to send with API requests, as 'Name: Value'. Can be repeated
+    #[structopt(long = "header", number_of_values = 1, parse(try_from_str = parse_header))]
+    headers: Vec<RequestHeader>,
+
+    /// Refuse connections negotiating a TLS version below this (1.0, 1.1, 1.2, or 1.3)
+    #[structopt(long, alias = "verify-tls-version", parse(try_from_str = parse_tls_version))]
+    min_tls_version: Option<tls::Version>,
+
+    /// Use HTTP/2 for API requests without negotiating it first, for registries
+    /// known to support it
+    #[structopt(long)]
+    http2_prior_knowledge: bool,
+
+    /// Append each opened link to this JSON Lines session file
+    #[structopt(long, parse(from_os_str))]
+    record_session: Option<PathBuf>,
+
+    /// Open every link recorded in this session file, then exit
+    #[structopt(long, parse(from_os_str))]
+    replay_session: Option<PathBuf>,
+
+    /// Answer link lookups over HTTP on this local port, for editor integrations,
+    /// until stopped
+    #[structopt(long, value_name = "port")]
+    serve: Option<u16>,
+
+    /// Command to run before opening each link, like 'check-url {url}'; the link
+    /// is only opened if it succeeds. Overrides pre_open_cmd in the config
+    #[structopt(long)]
+    pre_open_cmd: Option<String>,
+
+    /// Command to open links with instead of the default browser, like
+    /// 'firefox --new-window {url}'; the link is added at the end without {url}
+    #[structopt(long)]
+    opener_cmd: Option<String>,
+
+    /// Wait for the --opener-cmd command to exit after opening each link, like
+    /// when a script needs the browser closed before going on
+    #[structopt(long, requires = "opener_cmd")]
+    wait: bool,
+
+    /// Command that prints the crate's info as JSON, run with the crate name
+    /// instead of looking it up on crates.io
+    #[structopt(long)]
+    resolver: Option<String>,
+
+    /// GitHub API token, used for looking up details like open pull request
+    /// counts (defaults to the GITHUB_TOKEN environment variable)
+    #[structopt(long)]
+    github_token: Option<GithubToken>,
+
+    /// Ignore environment variables, including proxy settings, and only use
+    /// command line arguments and built-in defaults
+    #[structopt(long)]
+    no_env: bool,
+
+    /// With the pullrequests destination, print how many pull requests are open
+    /// first (requires a GitHub token)
+    #[structopt(long)]
+    open_pr_count: bool,
+
+    /// When opening several links, open at most this many per host
+    #[structopt(long)]
+    open_limit_host: Option<usize>,
+
+    /// Print the crates you navigate to most, then exit, like `cargo nav stats`
+    #[structopt(long)]
+    stats: bool,
+
+    /// Count this navigation in the local stats file. Overrides record_stats in
+    /// the config
+    #[structopt(long)]
+    record_stats: bool,
+
+    /// Treat the crate name as a file listing crate names, one per line (or "-"
+    /// for stdin), and print the link for each instead of opening it
+    #[structopt(long)]
+    batch: bool,
+
+    /// With --batch or --depth, how many crates to look up at once (by default,
+    /// as many as there are CPUs)
+    #[structopt(long)]
+    jobs: Option<usize>,
 
-    #[structopt(possible_values = &Destination::variants(), case_insensitive = true, default_value = "c")]
-    destination: Destination,
+    /// With --batch, list the crates by name, most downloaded first, or most
+    /// recently updated first, instead of in the order given
+    #[structopt(
+        long,
+        possible_values = &CrateOrder::variants(),
+        case_insensitive = true
+    )]
+    order: Option<CrateOrder>,
+
+    /// Treat the crate name as a search query and list the matching crates
+    /// instead of opening one
+    #[structopt(long)]
+    search: bool,
+
+    /// With --search, also print how many crates matched in total
+    #[structopt(long)]
+    count: bool,
+
+    /// With --search, open the crate named exactly as searched for, and fail if
+    /// there isn't one, instead of listing the matches
+    #[structopt(long)]
+    exact_match_only: bool,
+
+    /// Check that the link responds before opening it, and stop if it's broken
+    #[structopt(long)]
+    check: bool,
+
+    /// With --check, report a redirect's own status instead of following it
+    #[structopt(long)]
+    no_follow_redirect: bool,
+
+    crate_name: Option<String>,
+
+    /// One of: [c]rate, [h]omepage, [d]ocumentation, [r]epository, [f]orum,
+    /// featureflags, settings, security, advisory, pullrequests, team, compare,
+    /// versions, coverage, ownerprofile, similarcrates, archivedversion, archive,
+    /// discussions, containerregistry, sourcegraph, homepageordocs, rawindex,
+    /// reproduciblebuilds
+    #[structopt(parse(try_from_str = parse_destination))]
+    destination: Option<Destination>,
+
+    #[structopt(subcommand)]
+    command: Option<Command>,
 }
 
 /// Crate info JSON struct.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize)]
 struct CrateInfo {
     name: String,
     homepage: Option<String>,
     documentation: Option<String>,
     repository: Option<String>,
+    /// When the crate was last changed, as an RFC 3339 timestamp.
+    #[serde(default)]
+    updated_at: Option<String>,
+    /// All-time download count.
+    #[serde(default)]
+    downloads: Option<u64>,
+    /// Downloads in the last 90 days.
+    #[serde(default)]
+    recent_downloads: Option<u64>,
+    /// Newest published version, not counting pre-releases.
+    #[serde(default)]
+    newest_version: Option<String>,
+    /// Highest published version, including pre-releases.
+    #[serde(default)]
+    max_version: Option<String>,
+    /// Slugs of the crates.io categories the crate is in.
+    #[serde(default)]
+    categories: Vec<String>,
+    /// Short description of the crate.
+    #[serde(default)]
+    description: Option<String>,
+    /// Keywords the crate is tagged with.
+    #[serde(default)]
+    keywords: Vec<String>,
+    /// Published versions, newest first, from the top level of the API response.
+    #[serde(skip)]
+    versions: Vec<VersionInfo>,
+    /// Users and teams that own the crate, when they've been fetched.
+    #[serde(skip)]
+    owners: Vec<Owner>,
 }
 
-impl fmt::Display for CrateInfo {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.homepage.is_none() && self.documentation.is_none() && self.repository.is_none() {
-            return write!(
-                f,
-                "no links found for crate '{name}'; check https://crates.io/crates/{name}",
-                name = self.name,
-            );
+/// A crate owner, from the owners API endpoint.
+#[derive(Clone, Debug, Deserialize)]
+struct Owner {
+    /// Username, or `github:<org>:<team>` for teams.
+    login: String,
+    /// "user" or "team".
+    #[serde(default)]
+    kind: Option<String>,
+}
+
+impl Owner {
+    /// Whether the owner is a team rather than a person.
+    fn is_team(&self) -> bool {
+        match &self.kind {
+            Some(kind) => kind == "team",
+            None => self.login.starts_with("github:"),
         }
-        let pairs = [
-            ("Homepage", &self.homepage),
-            ("Documentation", &self.documentation),
-            ("Repository", &self.repository),
-        ];
-        let buffer = pairs
-            .iter()
-            .filter(|(_, link)| link.is_some())
-            .map(|(label, link)| format!("{}: {}", label, link.as_ref().unwrap()))
-            .collect::<Vec<_>>()
-            .join(", ");
-        write!(f, "{}", buffer)
     }
 }
 
-/// Top-level crates.io API response data.
+/// Build status of a crate version's docs, from docs.rs.
 #[derive(Debug, Deserialize)]
-struct CrateInfoWrapper {
-    #[serde(rename = "crate")]
-    crate_info: CrateInfo,
+struct DocsStatus {
+    doc_status: bool,
 }
 
-/// Set up logging based on whether or not the user wants to see debug logging.
-fn setup_logging(debug: bool) -> Result<()> {
-    let base_config = if debug {
-        Dispatch::new()
-            .level(LevelFilter::Debug)
-            .level_for("hyper::proto", LevelFilter::Info)
-    } else {
-        Dispatch::new().level(LevelFilter::Info)
-    };
-    let colors = ColoredLevelConfig::new().error(Color::Red);
-    let stdout_config = Dispatch::new()
-        .format(move |out, message, record| {
-            if record.level() == LevelFilter::Info {
-                out.finish(format_args!("{}", message))
-            } else {
-                out.finish(format_args!(
-                    "[{}] {} {}",
-                    record.target(),
-                    colors.color(record.level()),
-                    message
-                ))
-            }
-        })
-        .chain(io::stdout());
-    base_config.chain(stdout_config).apply()?;
-    Ok(())
+/// Response from the user API endpoint.
+#[derive(Debug, Deserialize)]
+struct UserResponse {
+    user: User,
 }
 
-fn get_api_url() -> String {
-    #[cfg(not(test))]
-    return String::from("https://crates.io/api/v1/crates");
-    #[cfg(test)]
-    return mockito::server_url();
+/// A crates.io user.
+#[derive(Debug, Deserialize)]
+struct User {
+    id: u64,
 }
 
-/// Get info from a crate from the crates.io API.
-fn get_crate_info(crate_name: &str) -> Result<CrateInfo> {
-    debug!("Requesting crate info from crates.io API");
-    let client = reqwest::blocking::Client::builder()
-        .user_agent("cargo-nav (https://github.com/celeo/cargo-nav)")
-        .build()?;
-    let resp = client
-        .get(format!("{}/{}", get_api_url(), crate_name))
-        .send()?;
-    if !resp.status().is_success() {
-        return Err(anyhow!(
-            "Got bad status {} from crates.io API",
-            resp.status()
-        ));
-    }
-    let data: CrateInfoWrapper = resp.json()?;
-    Ok(data.crate_info)
+/// Page of a crate listing, from the crates API endpoint.
+#[derive(Debug, Deserialize)]
+struct CratesPage {
+    crates: Vec<CrateInfo>,
+    #[serde(default)]
+    meta: CratesMeta,
 }
 
-/// Determine which URL to open.
-fn determine_link(info: &CrateInfo, destination: &Destination) -> Result<String> {
-    let crate_url = Some(format!("https://crates.io/crates/{}", info.name));
-    let pair = match destination {
-        Destination::C | Destination::Crate => ("crate", &crate_url),
-        Destination::H | Destination::Homepage => ("homepage", &info.homepage),
-        Destination::D | Destination::Documentation => ("documentation", &info.documentation),
-        Destination::R | Destination::Repository => ("repository", &info.repository),
-    };
-    match pair.1 {
-        Some(u) => Ok(u.to_owned()),
-        None => Err(anyhow!("The {} link isn't set for that crate", pair.0)),
-    }
+/// Paging details of a crate listing.
+#[derive(Debug, Default, Deserialize)]
+struct CratesMeta {
+    /// Query string for the next page, like `?page=2&per_page=100&user_id=1`.
+    #[serde(default)]
+    next_page: Option<String>,
 }
 
-/// Entrypoint.
-fn main() {
-    // conditionally skip 1 to provide running through both 'cargo nav' and 'cargo-nav'
-    let args: Vec<_> = env::args().collect();
-    let args = if args.len() > 1 && args[1] == "nav" {
-        args.iter().skip(1).cloned().collect::<Vec<_>>()
-    } else {
-        args
-    };
+/// Response from the crate versions API endpoint.
+#[derive(Debug, Deserialize)]
+struct VersionsResponse {
+    versions: Vec<VersionInfo>,
+}
 
-    let opt = Options::from_iter(args.iter());
-    if let Err(e) = setup_logging(opt.debug) {
-        eprintln!("Error setting up: {}", e);
-        process::exit(1);
-    }
-    debug!("CLI options: {:?}", opt);
-    let info = match get_crate_info(&opt.crate_name) {
-        Ok(i) => {
-            debug!("API info: {:?}", i);
-            i
-        }
-        Err(e) => {
-            debug!("Error getting crate info: {}", e);
-            error!(
-                r#"Could not find crate information for "{}""#,
-                opt.crate_name
-            );
-            process::exit(1);
-        }
-    };
-    let url = match determine_link(&info, &opt.destination) {
-        Ok(u) => u,
-        Err(e) => {
-            error!("Error determining link: {}", e);
-            info!("Here is the info that was found: {}", info);
-            process::exit(1);
-        }
-    };
-    debug!("URL to open: {}", url);
-    if let Err(e) = webbrowser::open(&url) {
-        debug!("Error opening link: {}", e);
-        error!("Could not open the link");
-        process::exit(1);
-    };
+/// A dependency of a crate version, from the dependencies API endpoint.
+#[derive(Debug, Deserialize)]
+struct Dependency {
+    /// Name of the crate depended on.
+    crate_id: String,
+    /// "normal", "build", or "dev".
+    #[serde(default)]
+    kind: Option<String>,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::{determine_link, get_crate_info, CrateInfo, Destination};
-    use mockito::mock;
+/// Response from the crate version dependencies API endpoint.
+#[derive(Debug, Deserialize)]
+struct DependenciesResponse {
+    dependencies: Vec<Dependency>,
+}
 
-    fn crate_info() -> CrateInfo {
-        CrateInfo {
-            name: "a".to_owned(),
-            homepage: Some("b".to_owned()),
-            documentation: Some("c".to_owned()),
-            repository: None,
+/// Response from the crate owners API endpoint.
+#[derive(Debug, Deserialize)]
+struct OwnersResponse {
+    users: Vec<Owner>,
+}
+
+/// A published version of a crate.
+#[derive(Clone, Debug, Default, Deserialize)]
+struct VersionInfo {
+    num: String,
+    #[serde(default)]
+    features: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    yanked: bool,
+    /// Size of the `.crate` file in bytes; not recorded for very old versions.
+    #[serde(default)]
+    crate_size: Option<u64>,
+    /// When the version was published, as an RFC 3339 timestamp.
+    #[serde(default)]
+    created_at: Option<String>,
+}
+
+impl VersionInfo {
+    /// Requested features that this version doesn't have.
+    fn unknown_features<'a>(&self, requested: &'a [String]) -> Vec<&'a str> {
+        requested
+            .iter()
+            .filter(|f| !self.features.contains_key(f.as_str()))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Optional dependencies a feature turns on, directly or through other features.
+    fn optional_dependencies(&self, feature: &str) -> Vec<String> {
+        let mut found = Vec::new();
+        let mut pending = vec![feature.to_owned()];
+        let mut seen = Vec::new();
+        while let Some(current) = pending.pop() {
+            if seen.contains(&current) {
+                continue;
+            }
+            for enabled in self.features.get(&current).into_iter().flatten() {
+                if let Some(dep) = enabled.strip_prefix("dep:") {
+                    found.push(dep.to_owned());
+                } else if self.features.contains_key(enabled) {
+                    pending.push(enabled.clone());
+                } else if !enabled.contains('/') {
+                    // an optional dependency's implicit feature
+                    found.push(enabled.clone());
+                }
+            }
+            seen.push(current);
         }
+        found.sort();
+        found.dedup();
+        found
     }
+}
 
-    #[test]
-    fn test_determine_link_short() {
-        let url = determine_link(&crate_info(), &Destination::D).unwrap();
-        assert_eq!(url, "c");
+impl CrateInfo {
+    /// The given version, or the newest one that isn't yanked.
+    fn version(&self, num: Option<&str>) -> Option<&VersionInfo> {
+        match num {
+            Some(n) => self.versions.iter().find(|v| v.num == n),
+            None => self.versions.iter().find(|v| !v.yanked),
+        }
     }
 
-    #[test]
-    fn test_determine_link_long() {
-        let url = determine_link(&crate_info(), &Destination::Homepage).unwrap();
-        assert_eq!(url, "b");
+    /// Whether the most recently published version has been yanked.
+    fn latest_yanked(&self) -> bool {
+        self.versions.first().is_some_and(|v| v.yanked)
     }
 
-    #[test]
-    fn determine_link_missing() {
-        let result = determine_link(&crate_info(), &Destination::Repository);
-        assert!(result.is_err());
+    /// The link fields that are set, with their names.
+    fn links(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("homepage", &self.homepage),
+            ("documentation", &self.documentation),
+            ("repository", &self.repository),
+        ]
+        .iter()
+        .filter_map(|(label, link)| link.as_deref().map(|l| (*label, l)))
+        .collect()
     }
 
-    #[test]
-    fn test_get_crate_info_just_name() {
-        let _m = mock("GET", "/a")
-            .with_body(r#"{"crate":{"name":"a"}}"#)
-            .create();
-        let info = get_crate_info("a").unwrap();
-        assert_eq!(info.name, "a");
-        assert_eq!(info.homepage, None);
-        assert_eq!(info.documentation, None);
-        assert_eq!(info.repository, None);
-        _m.assert();
+    /// Every link field, including unset ones.
+    fn candidate_links(&self) -> String {
+        [
+            ("homepage", &self.homepage),
+            ("documentation", &self.documentation),
+            ("repository", &self.repository),
+        ]
+        .iter()
+        .map(|(label, link)| format!("{} = {}", label, link.as_deref().unwrap_or("(not set)")))
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+
+    /// The crate's links on one line, or where to look when it has none.
+    fn describe(&self, config: &Config) -> String {
+        let pairs = [
+            ("Homepage", &self.homepage),
+            ("Documentation", &self.documentation),
+            ("Repository", &self.repository),
+        ];
+        let links = pairs
+            .iter()
+            .filter_map(|(label, link)| link.as_ref().map(|l| format!("{}: {}", label, l)))
+            .collect::<Vec<_>>();
+        let mut description = if links.is_empty() {
+            format!(
+                "no links found for crate '{}'; check {}",
+                self.name,
+                config.crate_page(&self.name)
+            )
+        } else {
+            links.join(", ")
+        };
+        if self.latest_yanked() {
+            description.push_str(" (latest version yanked)");
+        }
+        description
+    }
+}
+
+/// Result of a lookup, as printed by `--json`.
+///
+/// Failures are reported in the JSON itself rather than through the exit code,
+/// so scripts don't need to read stderr.
+#[derive(Debug, Serialize)]
+struct JsonOutput {
+    name: String,
+    found: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    homepage: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    documentation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repository: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    /// Size in bytes of the version's `.crate` file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    crate_size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    downloads: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recent_downloads: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_30_days: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prior_30_days: Option<u64>,
+    /// Whether the last 30 days' downloads went "up", "down", or held "steady".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    download_trend: Option<&'static str>,
+}
+
+impl JsonOutput {
+    /// Output for a crate that was found, with the outcome of determining its link.
+    fn found(info: &CrateInfo, version: Option<&str>, link: &Result<String>) -> Self {
+        JsonOutput {
+            name: info.name.clone(),
+            found: true,
+            error: link.as_ref().err().map(|e| e.to_string()),
+            homepage: info.homepage.clone(),
+            documentation: info.documentation.clone(),
+            repository: info.repository.clone(),
+            url: link.as_ref().ok().cloned(),
+            crate_size: info.version(version).and_then(|v| v.crate_size),
+            downloads: info.downloads,
+            recent_downloads: info.recent_downloads,
+            last_30_days: None,
+            prior_30_days: None,
+            download_trend: None,
+        }
+    }
+
+    /// Add the recent download trend, when it could be looked up.
+    fn with_trend(self, trend: Option<&DownloadTrend>) -> Self {
+        JsonOutput {
+            last_30_days: trend.map(|t| t.last_30_days),
+            prior_30_days: trend.map(|t| t.prior_30_days),
+            download_trend: trend.map(DownloadTrend::direction),
+            ..self
+        }
+    }
+
+    /// Output for a crate that couldn't be looked up.
+    fn not_found(crate_name: &str, err: &anyhow::Error) -> Self {
+        JsonOutput {
+            name: crate_name.to_owned(),
+            found: false,
+            error: Some(if is_not_found(err) {
+                String::from("not found")
+            } else {
+                err.to_string()
+            }),
+            homepage: None,
+            documentation: None,
+            repository: None,
+            url: None,
+            crate_size: None,
+            downloads: None,
+            recent_downloads: None,
+            last_30_days: None,
+            prior_30_days: None,
+            download_trend: None,
+        }
+    }
+}
+
+/// Error response from the crates.io API.
+#[derive(Debug)]
+enum ApiError {
+    /// The API responded with a non-success status.
+    BadStatus(reqwest::StatusCode),
+    /// The response couldn't be read, even leniently; holds the request URL.
+    UnexpectedShape(String),
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ApiError::BadStatus(status) => {
+                write!(f, "Got bad status {} from crates.io API", status)
+            }
+            ApiError::UnexpectedShape(url) => write!(
+                f,
+                "The crates.io API returned data in an unexpected shape; please update \
+                 cargo-nav or file an issue (requested {})",
+                url
+            ),
+        }
+    }
+}
+
+impl error::Error for ApiError {}
+
+/// Whether the error is crates.io reporting that there's no such crate.
+fn is_not_found(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<ApiError>(),
+        Some(ApiError::BadStatus(status)) if *status == reqwest::StatusCode::NOT_FOUND
+    )
+}
+
+/// Top-level crates.io API response data.
+#[derive(Debug, Deserialize)]
+struct CrateInfoWrapper {
+    #[serde(rename = "crate")]
+    crate_info: CrateInfo,
+    #[serde(default)]
+    versions: Vec<VersionInfo>,
+}
+
+/// crates.io search API response data.
+#[derive(Debug, Deserialize)]
+struct SearchResults {
+    crates: Vec<CrateInfo>,
+    meta: SearchMeta,
+}
+
+/// Metadata about a search, alongside its results.
+#[derive(Debug, Deserialize)]
+struct SearchMeta {
+    /// Number of crates matching the query, not just the ones returned.
+    total: u64,
+}
+
+impl SearchResults {
+    /// How many of the matching crates were returned, e.g. "Showing 5 of 1234 matches".
+    fn summary(&self) -> String {
+        format!(
+            "Showing {} of {} matches",
+            self.crates.len(),
+            self.meta.total
+        )
+    }
+
+    /// The result named exactly `name`, ignoring fuzzy matches.
+    fn exact_match(&self, name: &str) -> Result<&CrateInfo> {
+        self.crates
+            .iter()
+            .find(|c| c.name == name)
+            .ok_or_else(|| anyhow!("No crate in the search results is named exactly '{}'", name))
+    }
+}
+
+/// Downloads on one day.
+#[derive(Debug, Deserialize)]
+struct DownloadBucket {
+    date: String,
+    downloads: u64,
+}
+
+/// Downloads not attributed to a recent version.
+#[derive(Debug, Default, Deserialize)]
+struct DownloadsMeta {
+    #[serde(default)]
+    extra_downloads: Vec<DownloadBucket>,
+}
+
+/// Response from the crate downloads API endpoint, with daily downloads for the
+/// last 90 days.
+#[derive(Debug, Deserialize)]
+struct DownloadsResponse {
+    #[serde(default)]
+    version_downloads: Vec<DownloadBucket>,
+    #[serde(default)]
+    meta: DownloadsMeta,
+}
+
+/// Download totals over recent periods.
+#[derive(Debug, Default, PartialEq)]
+struct DownloadTrend {
+    last_90_days: u64,
+    last_30_days: u64,
+    prior_30_days: u64,
+}
+
+impl DownloadTrend {
+    /// Add up daily downloads by how many days before `today` (days since the Unix
+    /// epoch) they happened.
+    fn from_buckets<'a>(buckets: impl IntoIterator<Item = &'a DownloadBucket>, today: i64) -> Self {
+        let mut trend = DownloadTrend::default();
+        for bucket in buckets {
+            let age = match days_since_epoch(&bucket.date) {
+                Some(day) => today - day,
+                None => continue,
+            };
+            if !(0..90).contains(&age) {
+                continue;
+            }
+            trend.last_90_days += bucket.downloads;
+            if age < 30 {
+                trend.last_30_days += bucket.downloads;
+            } else if age < 60 {
+                trend.prior_30_days += bucket.downloads;
+            }
+        }
+        trend
+    }
+
+    /// Whether the last 30 days saw more ("up") or fewer ("down") downloads than
+    /// the 30 before; a change within 10% counts as "steady".
+    fn direction(&self) -> &'static str {
+        let last = self.last_30_days as f64;
+        let prior = self.prior_30_days as f64;
+        if last > prior * 1.1 {
+            "up"
+        } else if last < prior * 0.9 {
+            "down"
+        } else {
+            "steady"
+        }
+    }
+
+    /// Arrow for the `direction` of the downloads.
+    fn arrow(&self) -> &'static str {
+        match self.direction() {
+            "up" => "↑",
+            "down" => "↓",
+            _ => "→",
+        }
+    }
+}
+
+/// Days since the Unix epoch of a date like "2023-05-04".
+fn days_since_epoch(date: &str) -> Option<i64> {
+    let date = NaiveDate::parse_from_str(date.get(..10)?, "%Y-%m-%d").ok()?;
+    Some((date - NaiveDate::from_ymd_opt(1970, 1, 1)?).num_days())
+}
+
+/// Parse an API response body.
+///
+/// If it doesn't match the expected shape, a lenient pass pulls out only the
+/// fields that are really needed, so additions or type changes to fields
+/// cargo-nav barely uses don't break lookups.
+fn parse_api_response<T: DeserializeOwned>(
+    body: &str,
+    url: &str,
+    lenient: fn(&Value) -> Option<T>,
+) -> Result<T> {
+    match serde_json::from_str(body) {
+        Ok(data) => Ok(data),
+        Err(e) => {
+            debug!(
+                "Unexpected response from {}, reading it leniently: {}",
+                url, e
+            );
+            serde_json::from_str::<Value>(body)
+                .ok()
+                .as_ref()
+                .and_then(lenient)
+                .ok_or_else(|| ApiError::UnexpectedShape(url.to_owned()).into())
+        }
+    }
+}
+
+/// String field of a JSON object, if it's there and a string.
+fn json_str(value: &Value, field: &str) -> Option<String> {
+    value.get(field)?.as_str().map(str::to_owned)
+}
+
+/// Read a version, needing only its number.
+fn lenient_version(value: &Value) -> Option<VersionInfo> {
+    Some(VersionInfo {
+        num: json_str(value, "num")?,
+        features: value
+            .get("features")
+            .and_then(|f| serde_json::from_value(f.clone()).ok())
+            .unwrap_or_default(),
+        yanked: value
+            .get("yanked")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+        crate_size: value.get("crate_size").and_then(Value::as_u64),
+        created_at: json_str(value, "created_at"),
+    })
+}
+
+/// Read a crate response, needing only the crate's name.
+fn lenient_crate_info(value: &Value) -> Option<CrateInfoWrapper> {
+    Some(CrateInfoWrapper {
+        crate_info: lenient_crate(value.get("crate")?)?,
+        versions: lenient_list(value, "versions", lenient_version),
+    })
+}
+
+/// Read a crate's details, needing only its name.
+fn lenient_crate(data: &Value) -> Option<CrateInfo> {
+    Some(CrateInfo {
+        name: json_str(data, "name").or_else(|| json_str(data, "id"))?,
+        homepage: json_str(data, "homepage"),
+        documentation: json_str(data, "documentation"),
+        repository: json_str(data, "repository"),
+        updated_at: json_str(data, "updated_at"),
+        downloads: data.get("downloads").and_then(Value::as_u64),
+        recent_downloads: data.get("recent_downloads").and_then(Value::as_u64),
+        newest_version: json_str(data, "newest_version"),
+        max_version: json_str(data, "max_version"),
+        description: json_str(data, "description"),
+        categories: json_strings(data, "categories"),
+        keywords: json_strings(data, "keywords"),
+        ..CrateInfo::default()
+    })
+}
+
+/// The strings in an array field, or none if it's missing.
+fn json_strings(data: &Value, field: &str) -> Vec<String> {
+    data.get(field)
+        .and_then(Value::as_array)
+        .map(|c| {
+            c.iter()
+                .filter_map(|v| v.as_str().map(str::to_owned))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Read a user response, which needs the user's ID.
+fn lenient_user(value: &Value) -> Option<UserResponse> {
+    let user = value.get("user")?;
+    Some(UserResponse {
+        user: User {
+            id: user.get("id")?.as_u64()?,
+        },
+    })
+}
+
+/// Read a page of a crate listing, keeping the crates that have names.
+fn lenient_crates_page(value: &Value) -> Option<CratesPage> {
+    value.get("crates")?.as_array()?;
+    Some(CratesPage {
+        crates: lenient_list(value, "crates", lenient_crate),
+        meta: CratesMeta {
+            next_page: value.get("meta").and_then(|m| json_str(m, "next_page")),
+        },
+    })
+}
+
+/// Read a versions response, keeping the versions that have numbers.
+fn lenient_versions(value: &Value) -> Option<VersionsResponse> {
+    value.get("versions")?.as_array()?;
+    Some(VersionsResponse {
+        versions: lenient_list(value, "versions", lenient_version),
+    })
+}
+
+/// Read an owners response, keeping the owners that have logins.
+fn lenient_owners(value: &Value) -> Option<OwnersResponse> {
+    value.get("users")?.as_array()?;
+    Some(OwnersResponse {
+        users: lenient_list(value, "users", |owner| {
+            Some(Owner {
+                login: json_str(owner, "login")?,
+                kind: json_str(owner, "kind"),
+            })
+        }),
+    })
+}
+
+/// Read a dependencies response, keeping the dependencies that have names.
+fn lenient_dependencies(value: &Value) -> Option<DependenciesResponse> {
+    value.get("dependencies")?.as_array()?;
+    Some(DependenciesResponse {
+        dependencies: lenient_list(value, "dependencies", |dependency| {
+            Some(Dependency {
+                crate_id: json_str(dependency, "crate_id")?,
+                kind: json_str(dependency, "kind"),
+            })
+        }),
+    })
+}
+
+/// Read the items of an array field that can be read, skipping the rest.
+fn lenient_list<T>(value: &Value, field: &str, read: fn(&Value) -> Option<T>) -> Vec<T> {
+    value
+        .get(field)
+        .and_then(Value::as_array)
+        .map(|items| items.iter().filter_map(read).collect())
+        .unwrap_or_default()
+}
+
+/// Minimum log levels, overall and for particular targets.
+#[derive(Debug, PartialEq)]
+struct LogLevels {
+    level: LevelFilter,
+    targets: Vec<(String, LevelFilter)>,
+}
+
+/// Work out the log levels from the `--log-level` flag (or `--debug`) and the
+/// config, which covers `CARGO_NAV_LOG`, in that order of precedence.
+///
+/// When debugging, `hyper::proto` is kept at info unless the config says otherwise,
+/// since it's very noisy.
+fn log_levels(flag: Option<LevelFilter>, config: &Config) -> Result<LogLevels> {
+    let level = flag.or(config.log_level()?).unwrap_or(LevelFilter::Info);
+    let mut targets = config.log_targets()?;
+    if level > LevelFilter::Info && !targets.iter().any(|(t, _)| t == "hyper::proto") {
+        targets.insert(0, ("hyper::proto".to_owned(), LevelFilter::Info));
+    }
+    Ok(LogLevels { level, targets })
+}
+
+/// Set up logging at the given levels, coloring warnings and errors if `color` is set.
+///
+/// Info messages go to stdout, or to stderr with `to_stderr`, when stdout is kept
+/// for output meant for other programs. Everything else goes to stderr.
+fn setup_logging(levels: &LogLevels, color: bool, to_stderr: bool) -> Result<()> {
+    let base_config = levels.targets.iter().fold(
+        Dispatch::new().level(levels.level),
+        |dispatch, (target, level)| dispatch.level_for(target.clone(), *level),
+    );
+    let colors = ColoredLevelConfig::new().error(Color::Red);
+    let output: Box<dyn io::Write + Send> = if to_stderr {
+        Box::new(io::stderr())
+    } else {
+        // info messages are output too, so they stop quietly when stdout is closed
+        Box::new(PipeWriter::new(io::stdout()))
+    };
+    let info_config = Dispatch::new()
+        .filter(|metadata| metadata.level() == LevelFilter::Info)
+        .format(|out, message, _| out.finish(format_args!("{}", message)))
+        .chain(output);
+    let diagnostic_config = Dispatch::new()
+        .filter(|metadata| metadata.level() != LevelFilter::Info)
+        .format(move |out, message, record| {
+            if !color {
+                out.finish(format_args!(
+                    "[{}] {} {}",
+                    record.target(),
+                    record.level(),
+                    message
+                ))
+            } else {
+                out.finish(format_args!(
+                    "[{}] {} {}",
+                    record.target(),
+                    colors.color(record.level()),
+                    message
+                ))
+            }
+        })
+        .chain(io::stderr());
+    base_config
+        .chain(info_config)
+        .chain(diagnostic_config)
+        .apply()?;
+    Ok(())
+}
+
+fn get_api_url() -> String {
+    #[cfg(not(test))]
+    return String::from("https://crates.io/api/v1/crates");
+    #[cfg(test)]
+    return mockito::server_url();
+}
+
+fn get_github_api_url() -> String {
+    #[cfg(not(test))]
+    return String::from("https://api.github.com");
+    #[cfg(test)]
+    return mockito::server_url();
+}
+
+fn get_github_raw_url() -> String {
+    #[cfg(not(test))]
+    return String::from("https://raw.githubusercontent.com");
+    #[cfg(test)]
+    return mockito::server_url();
+}
+
+fn get_docs_rs_url() -> String {
+    #[cfg(not(test))]
+    return String::from("https://docs.rs");
+    #[cfg(test)]
+    return mockito::server_url();
+}
+
+fn get_users_api_url() -> String {
+    #[cfg(not(test))]
+    return String::from("https://crates.io/api/v1/users");
+    #[cfg(test)]
+    return mockito::server_url();
+}
+
+fn get_static_url() -> String {
+    #[cfg(not(test))]
+    return String::from("https://static.crates.io/crates");
+    #[cfg(test)]
+    return mockito::server_url();
+}
+
+/// URL of the crate's crates.io API endpoint.
+fn crate_api_url(crate_name: &str) -> String {
+    urls::path(&get_api_url(), &[crate_name])
+}
+
+/// Settings for the HTTP client used for API requests.
+#[derive(Clone, Debug, Default)]
+struct ClientOptions {
+    /// Accept invalid TLS certificates, e.g. from an intercepting corporate proxy.
+    ignore_tls_errors: bool,
+    /// Lowest TLS version to accept, if not the library default.
+    min_tls_version: Option<tls::Version>,
+    /// Extra headers sent with every request.
+    headers: Vec<RequestHeader>,
+    /// Speak HTTP/2 right away instead of negotiating the protocol.
+    http2_prior_knowledge: bool,
+    /// Ignore proxies set in environment variables.
+    no_proxy: bool,
+    /// Return redirects instead of following them, to see where they point.
+    no_redirects: bool,
+}
+
+impl ClientOptions {
+    /// Client settings chosen on the command line. Proxies set in environment
+    /// variables are only used when the environment is read.
+    fn from_options(opt: &Options, environment: &Environment) -> Self {
+        ClientOptions {
+            ignore_tls_errors: opt.ignore_tls_errors,
+            min_tls_version: opt.min_tls_version,
+            headers: opt.headers.clone(),
+            http2_prior_knowledge: opt.http2_prior_knowledge,
+            no_proxy: !environment.enabled(),
+            no_redirects: false,
+        }
+    }
+}
+
+/// Number of results requested for `--search`.
+const SEARCH_RESULTS: usize = 10;
+
+/// Header names whose values shouldn't show up in debug logging.
+const SENSITIVE_HEADER_WORDS: &[&str] = &["auth", "cookie", "token", "key", "secret", "pass"];
+
+/// A user-supplied request header.
+#[derive(Clone)]
+struct RequestHeader {
+    name: HeaderName,
+    value: HeaderValue,
+}
+
+impl fmt::Debug for RequestHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.value.is_sensitive() {
+            write!(f, "{}: <redacted>", self.name)
+        } else {
+            write!(f, "{}: {:?}", self.name, self.value)
+        }
+    }
+}
+
+/// Parse a header given as "Name: Value".
+fn parse_header(s: &str) -> Result<RequestHeader> {
+    let (name, value) = s
+        .split_once(':')
+        .ok_or_else(|| anyhow!("Header '{}' should look like 'Name: Value'", s))?;
+    let name = HeaderName::from_bytes(name.trim().as_bytes())
+        .map_err(|_| anyhow!("Invalid header name '{}'", name.trim()))?;
+    let mut value = HeaderValue::from_str(value.trim())
+        .map_err(|_| anyhow!("Invalid value for header '{}'", name))?;
+    let lower = name.as_str();
+    if SENSITIVE_HEADER_WORDS.iter().any(|w| lower.contains(w)) {
+        value.set_sensitive(true);
+    }
+    Ok(RequestHeader { name, value })
+}
+
+/// A GitHub API token, kept out of debug logging.
+#[derive(Clone)]
+struct GithubToken(String);
+
+impl fmt::Debug for GithubToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<redacted>")
+    }
+}
+
+impl std::str::FromStr for GithubToken {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(GithubToken(s.to_owned()))
+    }
+}
+
+/// Parse a TLS version number like "1.2".
+fn parse_tls_version(s: &str) -> Result<tls::Version> {
+    match s {
+        "1.0" => Ok(tls::Version::TLS_1_0),
+        "1.1" => Ok(tls::Version::TLS_1_1),
+        "1.2" => Ok(tls::Version::TLS_1_2),
+        "1.3" => Ok(tls::Version::TLS_1_3),
+        _ => Err(anyhow!(
+            "Unknown TLS version '{}'; expected 1.0, 1.1, 1.2, or 1.3",
+            s
+        )),
+    }
+}
+
+/// Build the HTTP client used for API requests.
+fn build_client(options: &ClientOptions) -> Result<Client> {
+    let mut builder =
+        Client::builder().user_agent("cargo-nav (https://github.com/celeo/cargo-nav)");
+    if options.ignore_tls_errors {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    if let Some(version) = options.min_tls_version {
+        builder = builder.min_tls_version(version);
+    }
+    if options.http2_prior_knowledge {
+        builder = builder.http2_prior_knowledge();
+    }
+    if options.no_proxy {
+        builder = builder.no_proxy();
+    }
+    if options.no_redirects {
+        builder = builder.redirect(Policy::none());
+    }
+    if !options.headers.is_empty() {
+        let mut headers = HeaderMap::new();
+        for header in &options.headers {
+            headers.append(header.name.clone(), header.value.clone());
+        }
+        builder = builder.default_headers(headers);
+    }
+    Ok(builder.build()?)
+}
+
+/// Status the link answers a HEAD request with.
+fn check_link(client: &Client, url: &str) -> Result<reqwest::StatusCode> {
+    debug!("Checking {}", url);
+    Ok(client.head(url).send()?.status())
+}
+
+/// HTTP client built the first time it's needed, so runs that don't use the
+/// network don't set one up.
+struct LazyClient<'a> {
+    options: ClientOptions,
+    client: OnceCell<Client>,
+    /// Client that returns redirects instead of following them.
+    no_redirects: OnceCell<Client>,
+    timings: &'a Timings,
+}
+
+impl<'a> LazyClient<'a> {
+    fn new(options: ClientOptions, timings: &'a Timings) -> Self {
+        LazyClient {
+            options,
+            client: OnceCell::new(),
+            no_redirects: OnceCell::new(),
+            timings,
+        }
+    }
+
+    /// The client, building it if this is the first use.
+    fn get(&self) -> &Client {
+        self.init(&self.client, || self.options.clone())
+    }
+
+    /// A client set up the same way that doesn't follow redirects, for seeing
+    /// where they point.
+    fn no_redirects(&self) -> &Client {
+        self.init(&self.no_redirects, || ClientOptions {
+            no_redirects: true,
+            ..self.options.clone()
+        })
+    }
+
+    fn init<'c>(
+        &self,
+        cell: &'c OnceCell<Client>,
+        options: impl FnOnce() -> ClientOptions,
+    ) -> &'c Client {
+        cell.get_or_init(|| {
+            let client = match build_client(&options()) {
+                Ok(c) => c,
+                Err(e) => {
+                    error!("Error setting up the HTTP client: {}", e);
+                    process::exit(1);
+                }
+            };
+            self.timings.finish("client");
+            client
+        })
+    }
+}
+
+/// Get info from a crate from the crates.io API.
+fn get_crate_info(client: &Client, crate_name: &str) -> Result<CrateInfo> {
+    debug!("Requesting crate info from crates.io API");
+    let url = crate_api_url(crate_name);
+    let resp = client.get(&url).send()?;
+    if !resp.status().is_success() {
+        return Err(ApiError::BadStatus(resp.status()).into());
+    }
+    let data = parse_api_response(&resp.text()?, &url, lenient_crate_info)?;
+    Ok(CrateInfo {
+        versions: data.versions,
+        ..data.crate_info
+    })
+}
+
+/// Search crates.io for crates matching the query.
+fn search_crates(client: &Client, query: &str) -> Result<SearchResults> {
+    debug!("Searching crates.io for {}", query);
+    let resp = client
+        .get(get_api_url())
+        .query(&[("q", query), ("per_page", &SEARCH_RESULTS.to_string())])
+        .send()?;
+    if !resp.status().is_success() {
+        return Err(ApiError::BadStatus(resp.status()).into());
+    }
+    Ok(resp.json()?)
+}
+
+/// The crate's newest version, falling back to the first listed version.
+fn newest_version(info: &CrateInfo) -> Option<&str> {
+    info.newest_version
+        .as_deref()
+        .or_else(|| info.versions.first().map(|v| v.num.as_str()))
+}
+
+/// Message for when the newest version differs from the `previous` one seen.
+fn version_change(previous: Option<&str>, info: &CrateInfo) -> Option<String> {
+    let current = newest_version(info)?;
+    match previous {
+        None => Some(format!("{} {} is the newest version", info.name, current)),
+        Some(p) if p != current => Some(format!(
+            "{} has a new version: {} (was {})",
+            info.name, current, p
+        )),
+        Some(_) => None,
+    }
+}
+
+/// Check the crate every `interval`, printing when its newest version changes.
+fn watch(client: &Client, crate_name: &str, interval: Duration) -> ! {
+    let mut last = None;
+    loop {
+        match get_crate_info(client, crate_name) {
+            Ok(info) => {
+                if let Some(message) = version_change(last.as_deref(), &info) {
+                    info!("{}", message);
+                    last = newest_version(&info).map(str::to_owned);
+                }
+            }
+            Err(e) => warn!("Could not check {}: {}", crate_name, e),
+        }
+        thread::sleep(interval);
+    }
+}
+
+/// Body of the crates.io API response for the crate, before any deserialization.
+///
+/// JSON bodies are pretty-printed; anything else is returned as it came.
+fn get_raw_response(client: &Client, crate_name: &str) -> Result<String> {
+    let url = crate_api_url(crate_name);
+    debug!("Requesting the raw response from {}", url);
+    let resp = client.get(&url).send()?;
+    let status = resp.status();
+    let text = resp.text()?;
+    if !status.is_success() {
+        debug!("Error response body: {}", text);
+        return Err(ApiError::BadStatus(status).into());
+    }
+    Ok(match serde_json::from_str::<Value>(&text) {
+        Ok(value) => serde_json::to_string_pretty(&value)?,
+        Err(_) => text,
+    })
+}
+
+/// Size of the crate's `.crate` file for a version, according to the download server.
+fn download_size(client: &Client, crate_name: &str, version: &str) -> Result<u64> {
+    let url = urls::path(&crate_api_url(crate_name), &[version, "download"]);
+    debug!("Requesting download size from {}", url);
+    let resp = client.head(url).send()?;
+    if !resp.status().is_success() {
+        return Err(ApiError::BadStatus(resp.status()).into());
+    }
+    // a HEAD response has no body, so the size comes from the header itself
+    resp.headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| anyhow!("The download server didn't report a size"))
+}
+
+/// Number of open pull requests on a GitHub repository.
+///
+/// Only one pull request is requested; the total comes from the `x-total-count` header.
+fn open_pr_count(client: &Client, repo_url: &str, token: &GithubToken) -> Result<u64> {
+    let (owner, repo) = forge::github_owner_repo(repo_url)?;
+    let url = urls::query(
+        &urls::path(&get_github_api_url(), &["repos", &owner, &repo, "pulls"]),
+        &[("state", "open"), ("per_page", "1")],
+    );
+    debug!("Requesting open pull request count from {}", url);
+    let resp = client.get(url).bearer_auth(&token.0).send()?;
+    if !resp.status().is_success() {
+        return Err(anyhow!(
+            "Got bad status {} from the GitHub API",
+            resp.status()
+        ));
+    }
+    resp.headers()
+        .get("x-total-count")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| anyhow!("The GitHub API didn't report a pull request count"))
+}
+
+/// Recent download totals for a crate, from its daily download counts.
+fn get_download_trend(client: &Client, crate_name: &str) -> Result<DownloadTrend> {
+    debug!("Requesting download counts from crates.io API");
+    let resp = client
+        .get(format!("{}/downloads", crate_api_url(crate_name)))
+        .send()?;
+    if !resp.status().is_success() {
+        return Err(ApiError::BadStatus(resp.status()).into());
+    }
+    let data: DownloadsResponse = resp.json()?;
+    let today = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() / 86_400;
+    Ok(DownloadTrend::from_buckets(
+        data.version_downloads
+            .iter()
+            .chain(data.meta.extra_downloads.iter()),
+        today as i64,
+    ))
+}
+
+/// The crate's recent download trend, unless `offline`. It's only extra detail,
+/// so a failed lookup is logged and skipped.
+fn optional_download_trend(
+    client: &Client,
+    crate_name: &str,
+    offline: bool,
+) -> Option<DownloadTrend> {
+    if offline {
+        debug!("Skipping the download trend for --offline");
+        return None;
+    }
+    match get_download_trend(client, crate_name) {
+        Ok(t) => Some(t),
+        Err(e) => {
+            debug!("Error getting download counts: {}", e);
+            None
+        }
+    }
+}
+
+/// Download counts for `--info`, with the recent trend if it could be fetched.
+fn downloads_report(info: &CrateInfo, trend: Option<&DownloadTrend>) -> String {
+    let total = info
+        .downloads
+        .map(|d| d.to_string())
+        .unwrap_or_else(|| String::from("unknown"));
+    match (trend, info.recent_downloads) {
+        (Some(t), _) => format!(
+            "Downloads: {} total, {} in the last 90 days ({} {} in the last 30 days, {} in the 30 before)",
+            total,
+            t.last_90_days,
+            t.arrow(),
+            t.last_30_days,
+            t.prior_30_days
+        ),
+        (None, Some(recent)) => format!(
+            "Downloads: {} total, {} in the last 90 days",
+            total, recent
+        ),
+        (None, None) => format!("Downloads: {} total", total),
+    }
+}
+
+/// Get the README of the crate's GitHub repository.
+fn get_readme(client: &Client, info: &CrateInfo) -> Result<String> {
+    let repository = info
+        .repository
+        .as_deref()
+        .ok_or_else(|| anyhow!("The repository link isn't set for that crate"))?;
+    let (owner, repo) = forge::github_owner_repo(repository)?;
+    let url = urls::path(&get_github_raw_url(), &[&owner, &repo, "HEAD", "README.md"]);
+    debug!("Requesting README from {}", url);
+    let resp = client.get(url).send()?;
+    if !resp.status().is_success() {
+        return Err(anyhow!(
+            "Could not fetch the README: got status {}",
+            resp.status()
+        ));
+    }
+    Ok(resp.text()?)
+}
+
+/// Chat room link from the README of the crate's GitHub repository.
+fn community_link(client: &Client, info: &CrateInfo) -> Result<String> {
+    community::chat_link(&get_readme(client, info)?)
+        .ok_or_else(|| anyhow!("No Discord or Matrix link found in the README"))
+}
+
+/// Homepage (or documentation) link guessed from the README of the crate's
+/// GitHub repository, for crates that don't set a homepage.
+fn inferred_homepage(client: &Client, info: &CrateInfo) -> Result<String> {
+    readme::homepage_link(&get_readme(client, info)?)
+        .ok_or_else(|| anyhow!("No homepage link found in the README"))
+}
+
+/// Put the text on one line, cutting it off with an ellipsis if it's wider than `width`.
+fn truncate_to_width(text: &str, width: usize) -> String {
+    cut_to_width(&one_line(text), width)
+}
+
+/// The text's words on one line, separated by single spaces.
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Cut a line off with an ellipsis if it's wider than `width`.
+fn cut_to_width(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_owned();
+    }
+    let mut cut = text
+        .chars()
+        .take(width.saturating_sub(1))
+        .collect::<String>()
+        .trim_end()
+        .to_owned();
+    cut.push('…');
+    cut
+}
+
+/// Format a number of bytes for people, like "1.5 KiB".
+fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64;
+    let mut unit = "B";
+    for &u in UNITS {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = u;
+    }
+    format!("{:.1} {}", size, unit)
+}
+
+/// The crate's links, with details about a version, for `--info`.
+fn info_report(
+    info: &CrateInfo,
+    config: &Config,
+    version: Option<&str>,
+    desc_width: Option<usize>,
+) -> String {
+    let mut lines = vec![info.describe(config)];
+    if let Some(description) = &info.description {
+        let line = format!("Description: {}", description);
+        lines.push(match desc_width {
+            Some(width) => truncate_to_width(&line, width),
+            None => one_line(&line),
+        });
+    }
+    match info.version(version) {
+        Some(v) => {
+            lines.push(format!("Version: {}", v.num));
+            if let Some(created_at) = &v.created_at {
+                let date = created_at.split('T').next().unwrap_or(created_at);
+                lines.push(format!("Published: {}", date));
+            }
+            lines.push(format!(
+                "Size: {}",
+                v.crate_size
+                    .map(format_size)
+                    .unwrap_or_else(|| String::from("unknown"))
+            ));
+        }
+        None => lines.push(String::from("No version information found")),
+    }
+    lines.join("\n")
+}
+
+/// Get the users and teams that own a crate from the crates.io API.
+fn get_owners(client: &Client, crate_name: &str) -> Result<Vec<Owner>> {
+    debug!("Requesting crate owners from crates.io API");
+    let url = format!("{}/owners", crate_api_url(crate_name));
+    let resp = client.get(&url).send()?;
+    if !resp.status().is_success() {
+        return Err(ApiError::BadStatus(resp.status()).into());
+    }
+    let data = parse_api_response(&resp.text()?, &url, lenient_owners)?;
+    Ok(data.users)
+}
+
+/// Most pages of an author's crates to request, as a guard against paging forever.
+const MAX_AUTHOR_PAGES: usize = 50;
+
+/// Get every crate published by a user, from the crates.io API.
+///
+/// The listing is paged; pages are followed until there's no next one.
+fn get_author_crates(client: &Client, login: &str) -> Result<Vec<CrateInfo>> {
+    debug!("Requesting user from crates.io API");
+    let url = urls::path(&get_users_api_url(), &[login]);
+    let resp = client.get(&url).send()?;
+    if !resp.status().is_success() {
+        return Err(ApiError::BadStatus(resp.status()).into());
+    }
+    let user = parse_api_response(&resp.text()?, &url, lenient_user)?.user;
+    let mut crates = Vec::new();
+    let mut query = format!("?page=1&per_page=100&user_id={}", user.id);
+    for _ in 0..MAX_AUTHOR_PAGES {
+        let url = format!("{}{}", get_api_url(), query);
+        debug!("Requesting crates from {}", url);
+        let resp = client.get(&url).send()?;
+        if !resp.status().is_success() {
+            return Err(ApiError::BadStatus(resp.status()).into());
+        }
+        let page = parse_api_response(&resp.text()?, &url, lenient_crates_page)?;
+        crates.extend(page.crates);
+        match page.meta.next_page {
+            Some(next) => query = next,
+            None => return Ok(crates),
+        }
+    }
+    warn!(
+        "Stopped after {} pages of crates by {}",
+        MAX_AUTHOR_PAGES, login
+    );
+    Ok(crates)
+}
+
+/// Table of crates, numbered from 1, with their newest versions and descriptions,
+/// each row cut off at `width` if given.
+fn crates_table(crates: &[CrateInfo], width: Option<usize>) -> String {
+    let name_width = crates.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    let versions = crates
+        .iter()
+        .map(|c| {
+            newest_version(c)
+                .or(c.max_version.as_deref())
+                .unwrap_or("-")
+        })
+        .collect::<Vec<_>>();
+    let version_width = versions.iter().map(|v| v.len()).max().unwrap_or(0);
+    let number_width = crates.len().to_string().len();
+    crates
+        .iter()
+        .zip(versions)
+        .enumerate()
+        .map(|(i, (c, version))| {
+            let row = format!(
+                "{:>nw$}. {:<w$}  {:<vw$}  {}",
+                i + 1,
+                c.name,
+                version,
+                one_line(c.description.as_deref().unwrap_or("")),
+                nw = number_width,
+                w = name_width,
+                vw = version_width
+            );
+            let row = row.trim_end();
+            match width {
+                Some(width) => cut_to_width(row, width),
+                None => row.to_owned(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Get every published version of a crate, newest first, from the crates.io API.
+fn get_versions(client: &Client, crate_name: &str) -> Result<Vec<VersionInfo>> {
+    debug!("Requesting crate versions from crates.io API");
+    let url = format!("{}/versions", crate_api_url(crate_name));
+    let resp = client.get(&url).send()?;
+    if !resp.status().is_success() {
+        return Err(ApiError::BadStatus(resp.status()).into());
+    }
+    let data = parse_api_response(&resp.text()?, &url, lenient_versions)?;
+    Ok(data.versions)
+}
+
+/// Get the names of the normal (not build or dev) dependencies of a crate version.
+fn get_dependencies(client: &Client, crate_name: &str, version: &str) -> Result<Vec<String>> {
+    debug!("Requesting crate dependencies from crates.io API");
+    let url = urls::path(&crate_api_url(crate_name), &[version, "dependencies"]);
+    let resp = client.get(&url).send()?;
+    if !resp.status().is_success() {
+        return Err(ApiError::BadStatus(resp.status()).into());
+    }
+    let data = parse_api_response(&resp.text()?, &url, lenient_dependencies)?;
+    Ok(data
+        .dependencies
+        .into_iter()
+        .filter(|d| d.kind.as_deref().map_or(true, |kind| kind == "normal"))
+        .map(|d| d.crate_id)
+        .collect())
+}
+
+/// The crate's dependencies, level by level down to `depth`, stopping once
+/// there are `limit` of them. Each is only looked up and listed once, with the
+/// crates of a level looked up `jobs` at a time.
+fn collect_dependencies(
+    client: &Client,
+    info: &CrateInfo,
+    depth: usize,
+    limit: usize,
+    jobs: usize,
+) -> Result<Vec<CrateInfo>> {
+    let mut seen = vec![info.name.clone()];
+    let mut found: Vec<CrateInfo> = Vec::new();
+    let mut level = vec![info.clone()];
+    for _ in 0..depth {
+        let dependencies =
+            batch::fetch_all(&level, jobs, |current| match newest_version(current) {
+                Some(version) => get_dependencies(client, &current.name, version),
+                None => {
+                    debug!("No version of {} to read dependencies from", current.name);
+                    Ok(Vec::new())
+                }
+            });
+        let mut names = Vec::new();
+        'level: for dependencies in dependencies {
+            for dependency in dependencies? {
+                if found.len() + names.len() >= limit {
+                    break 'level;
+                }
+                if !seen.contains(&dependency) {
+                    seen.push(dependency.clone());
+                    names.push(dependency);
+                }
+            }
+        }
+        level = batch::fetch_all(&names, jobs, |name| get_crate_info(client, name))
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+        found.extend(level.iter().cloned());
+        if found.len() >= limit {
+            break;
+        }
+    }
+    Ok(found)
+}
+
+/// Links to the destination for the crate's dependencies, with `--depth`.
+///
+/// Each link comes with the dependency's name. Dependencies without a link for
+/// the destination are skipped.
+fn dependency_links(
+    client: &Client,
+    info: &CrateInfo,
+    destination: &Destination,
+    config: &Config,
+    depth: usize,
+    max_tabs: usize,
+    jobs: usize,
+) -> Result<Vec<(String, String)>> {
+    let dependencies = collect_dependencies(client, info, depth, max_tabs.saturating_sub(1), jobs)?;
+    let mut links = Vec::new();
+    for dependency in dependencies {
+        match determine_link(&dependency, destination, config) {
+            Ok(url) => links.push((dependency.name, url)),
+            Err(e) => info!("Skipping {}: {}", dependency.name, e),
+        }
+    }
+    Ok(links)
+}
+
+/// Listing of the crate's recent versions, or nothing, without making a request,
+/// when version checks are turned off.
+fn recent_versions(
+    client: &Client,
+    crate_name: &str,
+    limit: usize,
+    no_version_check: bool,
+) -> Result<Option<String>> {
+    if no_version_check {
+        debug!("Skipping the versions listing for --no-version-check");
+        return Ok(None);
+    }
+    Ok(Some(versions_listing(
+        &get_versions(client, crate_name)?,
+        limit,
+    )))
+}
+
+/// One line per version with its publish date, marking yanked versions.
+fn versions_listing(versions: &[VersionInfo], limit: usize) -> String {
+    versions
+        .iter()
+        .take(limit)
+        .map(|v| {
+            let date = v
+                .created_at
+                .as_deref()
+                .and_then(|c| c.split('T').next())
+                .unwrap_or("unknown date");
+            let yanked = if v.yanked { "  (yanked)" } else { "" };
+            format!("{}  {}{}", v.num, date, yanked)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Check that docs.rs built the docs for a version (or the latest one) successfully.
+fn check_documented(client: &Client, crate_name: &str, version: Option<&str>) -> Result<()> {
+    let version = version.unwrap_or("latest");
+    let url = urls::path(
+        &get_docs_rs_url(),
+        &["crate", crate_name, version, "status.json"],
+    );
+    debug!("Checking docs build status at {}", url);
+    let resp = client.get(url).send()?;
+    if !resp.status().is_success() {
+        return Err(anyhow!(
+            "Could not get the docs.rs build status: got status {}",
+            resp.status()
+        ));
+    }
+    let status: DocsStatus = resp.json()?;
+    if !status.doc_status {
+        return Err(anyhow!(
+            "docs.rs failed to build the docs for {} {}",
+            crate_name,
+            version
+        ));
+    }
+    Ok(())
+}
+
+/// URL of a team's crates.io page, from a team login like `github:rust-lang:libs`.
+fn team_url(web_base: &str, login: &str) -> Result<String> {
+    match login.split(':').collect::<Vec<_>>().as_slice() {
+        ["github", org, team] if !org.is_empty() && !team.is_empty() => Ok(urls::path(
+            web_base,
+            &["teams", &format!("github:{}:{}", org, team)],
+        )),
+        _ => Err(anyhow!(
+            "'{}' isn't a team login like github:<org>:<team>",
+            login
+        )),
+    }
+}
+
+/// Link to the crates.io profile of the crate's first owner that's a person.
+fn owner_profile_link(info: &CrateInfo, config: &Config) -> Option<String> {
+    let owner = info.owners.iter().find(|o| !o.is_team())?;
+    let url = urls::path(config.web_base(), &["users", &owner.login]);
+    debug!("Owner profile URL: {}", url);
+    Some(url)
+}
+
+/// Link to the page of the team that owns the crate.
+///
+/// When several teams own it, the user is asked to pick one (the first, with
+/// `--assume-yes`); if they can't be asked, the teams are listed in the error.
+fn team_link<R: BufRead>(
+    info: &CrateInfo,
+    config: &Config,
+    prompt: &mut Prompt<R>,
+) -> Result<String> {
+    let teams = info
+        .owners
+        .iter()
+        .filter(|o| o.is_team())
+        .map(|o| o.login.as_str())
+        .collect::<Vec<_>>();
+    let login = match teams.as_slice() {
+        [] => {
+            let users = info
+                .owners
+                .iter()
+                .map(|o| o.login.as_str())
+                .collect::<Vec<_>>();
+            return Err(if users.is_empty() {
+                anyhow!("No owners found for {}", info.name)
+            } else {
+                anyhow!(
+                    "No teams own {}; its owners are: {}",
+                    info.name,
+                    users.join(", ")
+                )
+            });
+        }
+        [team] => *team,
+        _ if prompt.available() => {
+            let mut question = format!("Several teams own {}:\n", info.name);
+            for (i, team) in teams.iter().enumerate() {
+                question.push_str(&format!("{}. {}\n", i + 1, team));
+            }
+            question.push_str("Team number: ");
+            let answer = prompt.ask(&question, "1")?.unwrap_or_default();
+            answer
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .and_then(|i| teams.get(i))
+                .copied()
+                .ok_or_else(|| anyhow!("'{}' isn't one of the team numbers", answer))?
+        }
+        _ => {
+            return Err(anyhow!(
+                "Several teams own {}: {}",
+                info.name,
+                teams.join(", ")
+            ))
+        }
+    };
+    team_url(config.web_base(), login)
+}
+
+/// Build crate info from a git dependency declared by the project containing `dir`.
+///
+/// Such crates have no crates.io metadata, so the only link is to the repository,
+/// at the pinned revision if there is one.
+fn git_dependency_info(crate_name: &str, dir: &Path) -> Result<CrateInfo> {
+    for path in manifest::find_manifests(dir) {
+        debug!("Checking {} for a git dependency", path.display());
+        if let Some(dep) = Manifest::load(&path)?.git_dependency(crate_name) {
+            return Ok(CrateInfo {
+                name: crate_name.to_owned(),
+                repository: Some(dep.web_url()),
+                ..CrateInfo::default()
+            });
+        }
+    }
+    Err(anyhow!(
+        r#""{}" isn't a git dependency of the current project"#,
+        crate_name
+    ))
+}
+
+/// A `[patch]` or `[replace]` entry affecting a crate in the current project.
+#[derive(Debug)]
+struct ProjectPatch {
+    patch: Patch,
+    /// Manifest the entry was found in.
+    manifest_path: PathBuf,
+    /// Version requirement the project declared before patching.
+    requested_version: Option<String>,
+}
+
+impl ProjectPatch {
+    /// Local directory of the replacement, for path patches.
+    fn local_dir(&self) -> Option<PathBuf> {
+        match &self.patch.source {
+            PatchSource::Path(relative) => {
+                let path = self.manifest_path.parent()?.join(relative);
+                Some(path.canonicalize().unwrap_or(path))
+            }
+            PatchSource::Git(_) => None,
+        }
+    }
+
+    /// Explanation of which source is used in place of crates.io.
+    fn notice(&self, crate_name: &str) -> String {
+        let replacement = match &self.patch.source {
+            PatchSource::Git(dep) => dep.web_url(),
+            PatchSource::Path(relative) => format!("local path {}", relative),
+        };
+        format!(
+            r#"Note: "{}" (requested version {}) is overridden by {} in {}; using {}"#,
+            crate_name,
+            self.requested_version.as_deref().unwrap_or("unknown"),
+            self.patch.section,
+            self.manifest_path.display(),
+            replacement
+        )
+    }
+}
+
+/// Find a `[patch]` or `[replace]` entry for the crate in the project containing `dir`.
+fn project_patch(crate_name: &str, dir: &Path) -> Result<Option<ProjectPatch>> {
+    let manifests = manifest::find_manifests(dir)
+        .into_iter()
+        .map(|path| Manifest::load(&path).map(|m| (path, m)))
+        .collect::<Result<Vec<_>>>()?;
+    let requested_version = manifests
+        .iter()
+        .find_map(|(_, m)| m.requested_version(crate_name))
+        .map(str::to_owned);
+    Ok(manifests.into_iter().find_map(|(manifest_path, m)| {
+        m.patch(crate_name).map(|patch| ProjectPatch {
+            patch,
+            manifest_path,
+            requested_version: requested_version.clone(),
+        })
+    }))
+}
+
+/// Find the local directory of a path dependency declared by the project containing `dir`.
+fn path_dependency_dir(crate_name: &str, dir: &Path) -> Result<Option<PathBuf>> {
+    for manifest_path in manifest::find_manifests(dir) {
+        if let Some(relative) = Manifest::load(&manifest_path)?.path_dependency(crate_name) {
+            let base = manifest_path.parent().unwrap_or(dir);
+            let path = base.join(relative);
+            return Ok(Some(path.canonicalize().unwrap_or(path)));
+        }
+    }
+    Ok(None)
+}
+
+/// Check the current project for a patch of the crate and, when `check_local`
+/// is set, a local directory to use in place of looking the crate up.
+///
+/// Neither is checked for `exact` names.
+fn project_source(
+    crate_name: &str,
+    exact: bool,
+    check_local: bool,
+    trace: &mut Trace,
+) -> (Option<ProjectPatch>, Option<PathBuf>) {
+    if exact {
+        trace.step("project", "not checked (--exact)");
+        return (None, None);
+    }
+    let patch = env::current_dir()
+        .map_err(anyhow::Error::from)
+        .and_then(|dir| project_patch(crate_name, &dir))
+        .unwrap_or_else(|e| {
+            debug!("Error checking for a patched dependency: {}", e);
+            None
+        });
+    trace.step(
+        "project patch",
+        patch
+            .as_ref()
+            .map_or_else(|| "none".to_owned(), |p| p.notice(crate_name)),
+    );
+    if !check_local {
+        return (patch, None);
+    }
+    let local = match patch.as_ref().and_then(ProjectPatch::local_dir) {
+        Some(dir) => Some(dir),
+        None => env::current_dir()
+            .map_err(anyhow::Error::from)
+            .and_then(|dir| path_dependency_dir(crate_name, &dir))
+            .unwrap_or_else(|e| {
+                debug!("Error checking for a path dependency: {}", e);
+                None
+            }),
+    };
+    trace.step(
+        "local directory",
+        local
+            .as_ref()
+            .map_or_else(|| "none".to_owned(), |d| d.display().to_string()),
+    );
+    (patch, local)
+}
+
+/// Use the repository of a git patch in place of the crate's own.
+fn apply_git_patch(info: &mut CrateInfo, patch: Option<&ProjectPatch>, trace: &mut Trace) {
+    if let Some(ProjectPatch {
+        patch: Patch {
+            source: PatchSource::Git(dep),
+            ..
+        },
+        ..
+    }) = patch
+    {
+        let repository = dep.web_url();
+        trace.step("patched repository", &repository);
+        info.repository = Some(repository);
+    }
+}
+
+/// Open a local directory in the system's file manager.
+fn open_directory(dir: &Path) -> Result<()> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    let status = process::Command::new(opener).arg(dir).status()?;
+    // explorer exits non-zero even when it succeeds
+    if !status.success() && !cfg!(windows) {
+        return Err(anyhow!("{} exited with {}", opener, status));
+    }
+    Ok(())
+}
+
+/// Navigate to a crate that's a path dependency of the current project.
+///
+/// Documentation comes from `cargo doc`, and every other destination is the
+/// crate's local directory.
+fn navigate_path_dependency(
+    crate_name: &str,
+    dir: &Path,
+    destination: &Destination,
+    print: Option<Terminator>,
+    build_docs: bool,
+) -> Result<()> {
+    info!(
+        r#""{}" is a path dependency of this project at {}"#,
+        crate_name,
+        dir.display()
+    );
+    if let Destination::D | Destination::Documentation = destination {
+        if !build_docs {
+            info!(
+                "Build and open its docs with: {}",
+                command::cargo(&["doc", "--open", "-p", crate_name])
+            );
+            return Ok(());
+        }
+        let cargo = env::var("CARGO").unwrap_or_else(|_| String::from("cargo"));
+        let status = process::Command::new(cargo)
+            .args(["doc", "--open", "-p", crate_name])
+            .status()?;
+        if !status.success() {
+            return Err(anyhow!("cargo doc exited with {}", status));
+        }
+        return Ok(());
+    }
+    if let Some(terminator) = print {
+        output::print_link(&dir.display().to_string(), terminator);
+        return Ok(());
+    }
+    open_directory(dir)
+}
+
+/// Path of the crate's `cargo doc` output within a target directory.
+fn local_docs_path(target_dir: &Path, crate_name: &str) -> PathBuf {
+    target_dir
+        .join("doc")
+        .join(crate_name.replace('-', "_"))
+        .join("index.html")
+}
+
+/// Target directories that may hold the docs for the project containing `dir`:
+/// `CARGO_TARGET_DIR` if it's set, then each enclosing package's `target`, nearest first.
+fn target_dirs(dir: &Path, environment: &Environment) -> Vec<PathBuf> {
+    let mut dirs = environment
+        .var_os(environment::CARGO_TARGET_DIR)
+        .map(|d| vec![dir.join(d)])
+        .unwrap_or_default();
+    dirs.extend(
+        manifest::find_manifests(dir)
+            .iter()
+            .filter_map(|path| path.parent())
+            .map(|parent| parent.join("target")),
+    );
+    dirs
+}
+
+/// `file://` URL of the crate's locally built docs, from the first target directory
+/// that has them.
+fn local_docs_link(crate_name: &str, target_dirs: &[PathBuf]) -> Result<String> {
+    let path = target_dirs
+        .iter()
+        .map(|dir| local_docs_path(dir, crate_name))
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            anyhow!(
+                r#"No local docs found for "{}"; build them with: cargo doc -p {}"#,
+                crate_name,
+                crate_name
+            )
+        })?;
+    let path = path.canonicalize()?;
+    reqwest::Url::from_file_path(&path)
+        .map(String::from)
+        .map_err(|_| anyhow!("Could not make a URL for {}", path.display()))
+}
+
+/// Name to look the crate up by: lowercased, since that's how crates.io stores
+/// names, unless the name should be used `exact`ly as given.
+fn lookup_name(crate_name: &str, exact: bool) -> String {
+    if exact {
+        return crate_name.to_owned();
+    }
+    let lowered = crate_name.to_lowercase();
+    if lowered != crate_name {
+        debug!("Looking up {} as {}", crate_name, lowered);
+    }
+    lowered
+}
+
+/// Get crate info from crates.io, falling back to the current project's git
+/// dependencies if crates.io doesn't know the crate.
+fn find_crate_info(
+    client: &Client,
+    crate_name: &str,
+    project_only: bool,
+    trace: &mut Trace,
+) -> Result<CrateInfo> {
+    let from_project = |trace: &mut Trace| -> Result<CrateInfo> {
+        let info = git_dependency_info(crate_name, &env::current_dir()?)?;
+        trace.step("source", "git dependency of the current project");
+        info!(
+            r#""{}" is a git dependency of this project; no documentation is available for it"#,
+            crate_name
+        );
+        Ok(info)
+    };
+    if project_only {
+        return from_project(trace);
+    }
+    trace.step("API URL", crate_api_url(crate_name));
+    match get_crate_info(client, crate_name) {
+        Ok(info) => {
+            trace.step("source", "crates.io");
+            Ok(info)
+        }
+        Err(e) if is_not_found(&e) => {
+            trace.step("crates.io", "not found");
+            from_project(trace).map_err(|project_err: anyhow::Error| {
+                debug!("{}", project_err);
+                e
+            })
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Get crate info from the configured resolver command, or else from crates.io
+/// and the current project.
+fn lookup_crate_info(
+    client: &LazyClient,
+    crate_name: &str,
+    opt: &Options,
+    trace: &mut Trace,
+) -> Result<CrateInfo> {
+    match &opt.resolver {
+        Some(command) => {
+            trace.step("resolver", command);
+            external_crate_info(command, crate_name)
+        }
+        None => find_crate_info(client.get(), crate_name, opt.project_only, trace),
+    }
+}
+
+/// Get info for a crate from an external resolver command instead of crates.io.
+fn external_crate_info(command: &str, crate_name: &str) -> Result<CrateInfo> {
+    let text = resolver::run(command, crate_name)?;
+    serde_json::from_str(&text)
+        .map_err(|e| anyhow!("Could not read the resolver's crate info: {}", e))
+}
+
+/// Serialize `--json` output, as a lone object or wrapped in an array.
+fn json_text(output: &JsonOutput, as_array: bool) -> serde_json::Result<String> {
+    if as_array {
+        serde_json::to_string(&[output])
+    } else {
+        serde_json::to_string(output)
+    }
+}
+
+/// Print `--json` output, or send it to the `--emit-to` socket or pipe.
+fn print_json(output: &JsonOutput, as_array: bool, emit_to: Option<&Path>) {
+    let json = match json_text(output, as_array) {
+        Ok(j) => j,
+        Err(e) => {
+            error!("Could not serialize output: {}", e);
+            process::exit(1);
+        }
+    };
+    match emit_to {
+        Some(path) => {
+            if let Err(e) = emit::emit(path, &json) {
+                error!("{}", e);
+                process::exit(1);
+            }
+        }
+        None => output::print(&json),
+    }
+}
+
+/// Whether the crate was last updated on or after the date, in UTC.
+fn updated_since(info: &CrateInfo, date: NaiveDate) -> Result<bool> {
+    let updated_at = info
+        .updated_at
+        .as_deref()
+        .ok_or_else(|| anyhow!("crates.io didn't say when {} was updated", info.name))?;
+    let updated_at = DateTime::parse_from_rfc3339(updated_at)
+        .map_err(|e| anyhow!("Invalid updated_at '{}': {}", updated_at, e))?;
+    Ok(updated_at.naive_utc().date() >= date)
+}
+
+/// What to print instead of failing when the requested link is missing and
+/// `--no-open-on-missing` is set.
+fn missing_link_listing(info: &CrateInfo, config: &Config, err: &anyhow::Error) -> String {
+    format!("{}\nAvailable links: {}", err, info.describe(config))
+}
+
+/// Every destination's link for the crate that can be found without asking or
+/// fetching anything else, labeled with the destination's name.
+fn all_links(info: &CrateInfo, config: &Config) -> Vec<(&'static str, String)> {
+    let mut links: Vec<(&'static str, String)> = Vec::new();
+    for name in Destination::variants().iter() {
+        let destination = match name.parse::<Destination>() {
+            Ok(d) => d,
+            Err(_) => continue,
+        };
+        // homepageordocs only repeats the homepage or documentation link
+        if matches!(destination, Destination::HomepageOrDocs)
+            || links.iter().any(|(label, _)| *label == destination.label())
+        {
+            continue;
+        }
+        match determine_link(info, &destination, config) {
+            Ok(url) => links.push((destination.label(), url)),
+            Err(e) => debug!("No {} link: {}", destination.label(), e),
+        }
+    }
+    links
+}
+
+/// Format a Markdown link with the crate name as its text.
+fn markdown_link(crate_name: &str, url: &str) -> String {
+    format!("[{}]({})", crate_name, url)
+}
+
+/// Put the text on the system clipboard.
+fn copy_to_clipboard(text: &str) -> Result<()> {
+    let mut clipboard = arboard::Clipboard::new()?;
+    clipboard.set_text(text.to_owned())?;
+    Ok(())
+}
+
+/// Split URLs into those to open and those to skip, opening at most `limit` per host.
+///
+/// URLs without a host, like local files, aren't limited.
+fn limit_per_host<'a>(urls: &[&'a str], limit: usize) -> (Vec<&'a str>, Vec<&'a str>) {
+    let mut counts = HashMap::new();
+    urls.iter().copied().partition(|url| {
+        let host = match reqwest::Url::parse(url) {
+            Ok(parsed) => match parsed.host_str() {
+                Some(h) => h.to_owned(),
+                None => return true,
+            },
+            Err(_) => return true,
+        };
+        let count = counts.entry(host).or_insert(0);
+        *count += 1;
+        *count <= limit
+    })
+}
+
+/// Open a link, the same way for everything that opens one.
+///
+/// The pre-open command gets to decline the link first, which is noted and isn't
+/// an error. Then the link is recorded to the `session` file, if there is one,
+/// and handed to the opener. Errors running the pre-open command or opening the
+/// link are returned for the caller to report.
+fn open_link(
+    link: &SessionRecord,
+    config: &Config,
+    opener: &Opener,
+    session: Option<&Path>,
+    full_urls: bool,
+) -> Result<()> {
+    let url = link.url.as_str();
+    if let Some(cmd) = &config.pre_open_cmd {
+        if !hook::allows(cmd, url)? {
+            info!(
+                "Not opening {}: the pre-open command declined it",
+                display::url(url, full_urls)
+            );
+            return Ok(());
+        }
+    }
+    if let Some(path) = session {
+        if let Err(e) = session::append(path, link) {
+            error!("Could not record to {}: {}", path.display(), e);
+        }
+    }
+    debug!("Opening {}", url);
+    opener
+        .open(url)
+        .map_err(|e| anyhow!("Could not open {}: {}", display::url(url, full_urls), e))
+}
+
+/// Open every link in a session file, at most `host_limit` per host if given,
+/// recording them to the `session` file if there is one.
+fn replay_session(
+    path: &Path,
+    host_limit: Option<usize>,
+    config: &Config,
+    opener: &Opener,
+    session: Option<&Path>,
+    full_urls: bool,
+) -> Result<()> {
+    let records = session::read(path)?;
+    info!("Replaying {} links from {}", records.len(), path.display());
+    let urls = records.iter().map(|r| r.url.as_str()).collect::<Vec<_>>();
+    let (open, skipped) = limit_per_host(&urls, host_limit.unwrap_or(usize::MAX));
+    // the links to open are in the same order as their records
+    let mut open = open.into_iter().peekable();
+    for record in &records {
+        if open.peek() != Some(&record.url.as_str()) {
+            continue;
+        }
+        open.next();
+        if let Err(e) = open_link(record, config, opener, session, full_urls) {
+            error!("{}", e);
+        }
+    }
+    for url in skipped {
+        info!(
+            "Skipping {}: already opened the limit for its host",
+            display::url(url, full_urls)
+        );
+    }
+    Ok(())
+}
+
+/// Show long output, paging it if needed, and exit quietly if stdout was closed.
+fn show_long(pager: &Pager, text: &str) {
+    output::finish(pager.show(text), false);
+}
+
+/// Count a navigation in the local stats file.
+fn record_stats(crate_name: &str, destination: &Destination) -> Result<()> {
+    let path = stats::stats_path().ok_or_else(|| anyhow!("No data directory found"))?;
+    let mut stats = stats::Stats::load(&path)?;
+    stats.increment(crate_name, destination.label());
+    stats.save(&path)
+}
+
+/// Where the repository has permanently moved to, like after a rename on GitHub.
+///
+/// The client mustn't follow redirects. Redirects to the same repository, like
+/// from `http` to `https`, don't count.
+fn moved_repository(client: &Client, repository: &str) -> Result<Option<String>> {
+    let url = forge::normalize_repo_url(repository);
+    let resp = client.head(&url).send()?;
+    if !matches!(
+        resp.status(),
+        StatusCode::MOVED_PERMANENTLY | StatusCode::PERMANENT_REDIRECT
+    ) {
+        return Ok(None);
+    }
+    let location = resp
+        .headers()
+        .get(LOCATION)
+        .and_then(|l| l.to_str().ok())
+        .ok_or_else(|| anyhow!("{} redirects without a location", url))?;
+    let moved = reqwest::Url::parse(&url)?.join(location)?.to_string();
+    Ok(Some(moved).filter(|m| renames::is_move(&url, m)))
+}
+
+/// Where the repository has moved to, from the renames file or by checking it,
+/// remembering newly found moves.
+fn follow_rename(client: &LazyClient, repository: &str) -> Result<Option<String>> {
+    let path = renames::renames_path().ok_or_else(|| anyhow!("No data directory found"))?;
+    let mut renames = renames::Renames::load(&path)?;
+    if let Some(moved) = renames.get(repository) {
+        debug!("{} is known to have moved to {}", repository, moved);
+        return Ok(Some(moved.to_owned()));
+    }
+    let moved = moved_repository(client.no_redirects(), repository)?;
+    if let Some(new) = &moved {
+        renames.record(repository, new);
+        renames.save(&path)?;
+    }
+    Ok(moved)
+}
+
+/// Whether the repository should be checked for a rename before navigating.
+///
+/// That's only done when links are being verified with `--check`, or the forge is
+/// being asked about the repository anyway, and the link is built from it.
+fn checks_renames(opt: &Options, destination: &Destination) -> bool {
+    let probing = opt.check || opt.community || opt.open_pr_count;
+    !opt.no_follow_renames
+        && probing
+        && (destination.uses_repository() || opt.community || opt.open_repo_tree_at_version)
+}
+
+/// Switch the crate's repository to where it has moved to, if it has, returning
+/// the old location.
+fn follow_repository_rename(
+    client: &LazyClient,
+    info: &mut CrateInfo,
+    trace: &mut Trace,
+) -> Option<String> {
+    let repository = info.repository.clone()?;
+    match follow_rename(client, &repository) {
+        Ok(Some(moved)) => {
+            trace.step("repository moved to", &moved);
+            info.repository = Some(moved);
+            Some(repository)
+        }
+        Ok(None) => {
+            trace.step("repository moved to", "not moved");
+            None
+        }
+        Err(e) => {
+            debug!("Could not check whether the repository moved: {}", e);
+            trace.step("repository moved to", format!("unknown ({})", e));
+            None
+        }
+    }
+}
+
+/// Print the most navigated-to crates from the local stats file.
+fn print_stats(limit: usize) -> Result<()> {
+    let path = stats::stats_path().ok_or_else(|| anyhow!("No data directory found"))?;
+    output::print(&stats::Stats::load(&path)?.report(limit));
+    Ok(())
+}
+
+/// Version and build environment information.
+fn build_info() -> String {
+    let sha = env!("CARGO_NAV_GIT_SHA");
+    format!(
+        "cargo-nav {}\ncommit: {}\ntarget: {}",
+        env!("CARGO_PKG_VERSION"),
+        if sha.is_empty() { "unknown" } else { sha },
+        env!("CARGO_NAV_TARGET"),
+    )
+}
+
+/// Check connectivity to the API host after a network failure, reporting to stderr.
+fn report_diagnosis(err: &anyhow::Error) {
+    let is_network = err
+        .downcast_ref::<reqwest::Error>()
+        .is_some_and(|e| e.is_timeout() || e.is_connect());
+    if !is_network {
+        eprintln!("Not a network failure; skipping connectivity checks");
+        return;
+    }
+    let url = match reqwest::Url::parse(&get_api_url()) {
+        Ok(u) => u,
+        Err(e) => {
+            eprintln!("Could not parse the API URL: {}", e);
+            return;
+        }
+    };
+    let (host, port) = match (url.host_str(), url.port_or_known_default()) {
+        (Some(h), Some(p)) => (h, p),
+        _ => {
+            eprintln!("Could not determine the API host from {}", url);
+            return;
+        }
+    };
+    eprintln!("Checking connectivity to {}:{}", host, port);
+    eprintln!(
+        "{}",
+        diagnose::diagnose(host, port, diagnose::CONNECT_TIMEOUT)
+    );
+}
+
+/// Determine which destination to use, given that the user may not have specified one.
+///
+/// Without a destination the crate's `configured` default is used, then the crate
+/// page, unless the user has opted out of that, in which case they're asked to pick
+/// one (the crate page, with `--assume-yes`) or an error is returned.
+fn choose_destination<R: BufRead>(
+    destination: Option<Destination>,
+    configured: Option<Destination>,
+    no_default_crate_page: bool,
+    prompt: &mut Prompt<R>,
+    trace: &mut Trace,
+) -> Result<Destination> {
+    if let Some(d) = destination {
+        trace.step("destination", format!("{} (given)", d.label()));
+        return Ok(d);
+    }
+    if let Some(d) = configured {
+        trace.step("destination", format!("{} (crate_defaults)", d.label()));
+        return Ok(d);
+    }
+    if !no_default_crate_page {
+        trace.step("destination", "crate (default)");
+        return Ok(Destination::C);
+    }
+    let answer = prompt.ask(
+        "Destination [c]rate, [h]omepage, [d]ocumentation, [r]epository: ",
+        "crate",
+    )?;
+    match answer {
+        Some(a) => {
+            let d = parse_destination(&a)?;
+            trace.step("destination", format!("{} (asked)", d.label()));
+            Ok(d)
+        }
+        None => Err(anyhow!(
+            "No destination given; pass one of: {}",
+            Destination::variants().join(", ")
+        )),
+    }
+}
+
+/// Find a forum link for the crate.
+///
+/// A `forum_url` set for the crate in the config file takes priority, otherwise
+/// the crate's homepage is used if it points at a known forum.
+fn forum_link(info: &CrateInfo, config: &Config, trace: &mut Trace) -> Option<String> {
+    let configured = config
+        .crate_override(&info.name)
+        .and_then(|o| o.forum_url.clone());
+    trace.step(
+        "config forum_url",
+        configured.as_deref().unwrap_or("not set"),
+    );
+    if configured.is_some() {
+        return configured;
+    }
+    let homepage = info
+        .homepage
+        .clone()
+        .filter(|h| FORUM_PATTERNS.iter().any(|p| h.contains(p)));
+    trace.step(
+        "homepage forum pattern",
+        if homepage.is_some() {
+            "matched"
+        } else {
+            "no match"
+        },
+    );
+    homepage
+}
+
+/// Determine which URL to open.
+fn determine_link(info: &CrateInfo, destination: &Destination, config: &Config) -> Result<String> {
+    resolve_link(info, destination, config, &mut Trace::default())
+}
+
+/// Look up a crate's link for the HTTP server, with the crate page as the default.
+///
+/// Returns the crate's canonical name, the destination's name, and the link.
+fn served_link(
+    client: &Client,
+    config: &Config,
+    crate_name: &str,
+    destination: Option<&str>,
+) -> Result<(String, String, String)> {
+    let destination = parse_destination(destination.unwrap_or("crate"))?;
+    let mut info = get_crate_info(client, &lookup_name(crate_name, false))?;
+    if destination.needs_owners() {
+        info.owners = get_owners(client, &info.name)?;
+    }
+    let url = determine_link(&info, &destination, config)?;
+    Ok((info.name, destination.label().to_owned(), url))
+}
+
+/// Determine which URL to open, recording the rules evaluated along the way.
+fn resolve_link(
+    info: &CrateInfo,
+    destination: &Destination,
+    config: &Config,
+    trace: &mut Trace,
+) -> Result<String> {
+    let link = match destination {
+        Destination::C | Destination::Crate => Some(config.crate_page(&info.name)),
+        Destination::H | Destination::Homepage => info.homepage.clone(),
+        Destination::D | Destination::Documentation => info.documentation.clone(),
+        Destination::R | Destination::Repository => info.repository.clone(),
+        Destination::F | Destination::Forum => forum_link(info, config, trace),
+        Destination::FeatureFlags => Some(urls::path(
+            "https://featureflags.dev",
+            &["crates", &info.name],
+        )),
+        Destination::Settings => Some(urls::path(
+            config.web_base(),
+            &["crates", &info.name, "settings"],
+        )),
+        Destination::Security => github_link(info, |repo| forge::github_url(repo, "security"))?,
+        Destination::Advisory => Some(urls::path(
+            "https://rustsec.org",
+            &["packages", &format!("{}.html", info.name)],
+        )),
+        Destination::PullRequests => github_link(info, |repo| forge::github_url(repo, "pulls"))?,
+        Destination::ContainerRegistry => github_link(info, forge::container_registry_url)?,
+        Destination::Sourcegraph => Some(sourcegraph_link(info)),
+        Destination::ReproducibleBuilds => Some(urls::path(
+            "https://reproducible.rs",
+            &["crates", &info.name],
+        )),
+        Destination::RawIndex => Some(format!(
+            "https://index.crates.io/{}",
+            index_path(&info.name)
+        )),
+        Destination::HomepageOrDocs => first_available(
+            vec![
+                ("homepage", info.homepage.clone()),
+                ("documentation", info.documentation.clone()),
+                (
+                    "docs.rs",
+                    Some(urls::path("https://docs.rs", &[&info.name])),
+                ),
+            ],
+            trace,
+        ),
+        Destination::Team => Some(team_link(
+            info,
+            config,
+            &mut Prompt::new(io::empty(), false, false),
+        )?),
+        Destination::OwnerProfile => owner_profile_link(info, config),
+        Destination::SimilarCrates => Some(similar_crates_link(info, config)),
+        Destination::ArchivedVersion => Some(archived_version_link(info, None, config)?),
+        Destination::Archive => Some(archive_link(info, None)?),
+        Destination::Discussions => match &info.repository {
+            Some(repo) => {
+                if forge::forge(&forge::normalize_repo_url(repo)) == forge::Forge::GitLab {
+                    warn!("GitLab doesn't have discussions; opening the issues instead");
+                    trace.step("discussions", "not on GitLab; using issues");
+                }
+                Some(forge::discussions_url(repo)?)
+            }
+            None => None,
+        },
+        Destination::Compare => Some(compare_link(info, None, None)?),
+        Destination::Coverage => match &info.repository {
+            Some(repo) => Some(forge::coverage_url(repo)?),
+            None => None,
+        },
+        Destination::Versions => Some(urls::path(
+            config.web_base(),
+            &["crates", &info.name, "versions"],
+        )),
+    };
+    link.ok_or_else(|| anyhow!("The {} link isn't set for that crate", destination.label()))
+}
+
+/// The first of the named candidate links that's set, noting each one checked.
+fn first_available(candidates: Vec<(&str, Option<String>)>, trace: &mut Trace) -> Option<String> {
+    for (name, link) in candidates {
+        trace.step(name, link.as_deref().unwrap_or("not set"));
+        if link.is_some() {
+            return link;
+        }
+    }
+    None
+}
+
+/// Link for a destination that's only on GitHub, built from the crate's repository.
+///
+/// There's no link without a repository, and it's an error if the repository isn't
+/// on GitHub.
+fn github_link(info: &CrateInfo, build: fn(&str) -> Result<String>) -> Result<Option<String>> {
+    info.repository.as_deref().map(build).transpose()
+}
+
+/// The link with the docs.rs theme set, if it's to docs.rs.
+fn with_docs_theme(link: &str, theme: DocsTheme) -> String {
+    match reqwest::Url::parse(link) {
+        Ok(mut url) if url.host_str() == Some("docs.rs") => {
+            url.query_pairs_mut()
+                .append_pair("theme", &theme.to_string().to_lowercase());
+            url.to_string()
+        }
+        _ => {
+            debug!("Not setting the theme of {}, which isn't on docs.rs", link);
+            link.to_owned()
+        }
+    }
+}
+
+/// Path of the crate's file in the crates.io index, under its name-length prefix
+/// directories: `1/`, `2/`, or `3/<first letter>/` for short names, and
+/// `<first two>/<next two>/` otherwise.
+fn index_path(crate_name: &str) -> String {
+    let name = crate_name.to_lowercase();
+    let chars = name.chars().collect::<Vec<_>>();
+    let part = |range: &[char]| urls::segment(&range.iter().collect::<String>());
+    let prefix = match chars.len() {
+        n if n <= 2 => n.to_string(),
+        3 => format!("3/{}", part(&chars[..1])),
+        _ => format!("{}/{}", part(&chars[..2]), part(&chars[2..4])),
+    };
+    format!("{}/{}", prefix, urls::segment(&name))
+}
+
+/// Link to the crate's code on Sourcegraph: its repository, if that's on GitHub or
+/// GitLab, or otherwise Sourcegraph's copy of the published crate.
+fn sourcegraph_link(info: &CrateInfo) -> String {
+    info.repository
+        .as_deref()
+        .and_then(forge::sourcegraph_url)
+        .unwrap_or_else(|| urls::path("https://sourcegraph.com", &["crates", &info.name]))
+}
+
+/// Value of one of the `JSON_FIELDS` of the crate's info.
+fn json_field(info: &CrateInfo, field: &str, config: &Config) -> Option<String> {
+    match field {
+        "name" => Some(info.name.clone()),
+        "homepage" => info.homepage.clone(),
+        "documentation" => info.documentation.clone(),
+        "repository" => info.repository.clone(),
+        "updated_at" => info.updated_at.clone(),
+        "downloads" => info.downloads.map(|d| d.to_string()),
+        "recent_downloads" => info.recent_downloads.map(|d| d.to_string()),
+        "crate" => Some(config.crate_page(&info.name)),
+        _ => None,
+    }
+}
+
+/// Link to the crates.io page of the crate's first category, to find alternatives.
+///
+/// Crates without categories get a crates.io search for their name instead.
+fn similar_crates_link(info: &CrateInfo, config: &Config) -> String {
+    match info.categories.first() {
+        Some(category) => urls::path(config.web_base(), &["categories", category]),
+        None => urls::query(
+            &urls::path(config.web_base(), &["search"]),
+            &[("q", &info.name)],
+        ),
+    }
+}
+
+/// Link to the crate's docs.rs features page, highlighting the first requested
+/// feature that exists.
+///
+/// Unknown features are warned about, and the optional dependencies each known
+/// feature pulls in are listed.
+fn features_link(info: &CrateInfo, features: &[String], version: Option<&str>) -> String {
+    let mut anchor = String::new();
+    match info.version(version) {
+        Some(v) => {
+            for unknown in v.unknown_features(features) {
+                warn!("{} {} has no feature named '{}'", info.name, v.num, unknown);
+            }
+            for feature in features.iter().filter(|f| v.features.contains_key(*f)) {
+                let deps = v.optional_dependencies(feature);
+                if deps.is_empty() {
+                    info!("Feature '{}' enables no optional dependencies", feature);
+                } else {
+                    info!("Feature '{}' enables: {}", feature, deps.join(", "));
+                }
+                if anchor.is_empty() {
+                    anchor = urls::fragment(feature);
+                }
+            }
+        }
+        None => warn!("No feature information found for {}", info.name),
+    }
+    let page = urls::path(
+        "https://docs.rs",
+        &["crate", &info.name, version.unwrap_or("latest"), "features"],
+    );
+    format!("{}{}", page, anchor)
+}
+
+/// Link to the crate's repository source tree at a version's tag.
+fn repo_tree_link(info: &CrateInfo, version: Option<&str>) -> Result<String> {
+    let version =
+        version.ok_or_else(|| anyhow!("--open-repo-tree-at-version requires --crate-version"))?;
+    let repository = info
+        .repository
+        .as_deref()
+        .ok_or_else(|| anyhow!("The repository link isn't set for that crate"))?;
+    forge::repo_tree_url(repository, version)
+}
+
+/// Link to the crates.io page for a published version, showing its metadata as it
+/// was at publication. Without a version, the crate's `max_version` is used.
+fn archived_version_link(
+    info: &CrateInfo,
+    version: Option<&str>,
+    config: &Config,
+) -> Result<String> {
+    let version = match version.or(info.max_version.as_deref()) {
+        Some(v) => v,
+        None => return Err(anyhow!("No version to link to; pass --crate-version")),
+    };
+    if !info.versions.is_empty() && info.version(Some(version)).is_none() {
+        return Err(anyhow!("{} has no version {}", info.name, version));
+    }
+    Ok(urls::path(
+        config.web_base(),
+        &["crates", &info.name, version],
+    ))
+}
+
+/// Link to the `.crate` archive of a version, or of the crate's `max_version`.
+fn archive_link(info: &CrateInfo, version: Option<&str>) -> Result<String> {
+    let version = version
+        .or(info.max_version.as_deref())
+        .ok_or_else(|| anyhow!("No version to link to; pass --crate-version"))?;
+    Ok(urls::path(
+        &get_static_url(),
+        &[&info.name, &format!("{}-{}.crate", info.name, version)],
+    ))
+}
+
+/// Save the file at the URL into the directory, named after the URL's last segment.
+fn download_archive(client: &Client, url: &str, dir: &Path) -> Result<PathBuf> {
+    let file_name = url
+        .rsplit('/')
+        .next()
+        .filter(|n| !n.is_empty())
+        .ok_or_else(|| anyhow!("No file name in {}", url))?;
+    debug!("Downloading {}", url);
+    let resp = client.get(url).send()?;
+    if !resp.status().is_success() {
+        return Err(ApiError::BadStatus(resp.status()).into());
+    }
+    let path = dir.join(file_name);
+    fs::write(&path, resp.bytes()?)
+        .map_err(|e| anyhow!("Could not write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Link to the repository's comparison between two versions' tags.
+///
+/// With only one version, it's compared to the latest commit.
+fn compare_link(info: &CrateInfo, from: Option<&str>, to: Option<&str>) -> Result<String> {
+    let (from, to) = match (from, to) {
+        (Some(f), t) => (f, t),
+        (None, Some(t)) => (t, None),
+        (None, None) => {
+            return Err(anyhow!(
+                "The compare destination requires --from-version or --to-version"
+            ))
+        }
+    };
+    let repository = info
+        .repository
+        .as_deref()
+        .ok_or_else(|| anyhow!("The repository link isn't set for that crate"))?;
+    forge::compare_url(repository, from, to)
+}
+
+/// Caution to show when the crate's repository seems to be for a project with a
+/// different name, which typosquatting crates do to look legitimate.
+fn repository_mismatch(info: &CrateInfo) -> Option<String> {
+    let repository = info.repository.as_deref()?;
+    let project = forge::project_name(repository)?;
+    if forge::names_match(&info.name, &project) {
+        return None;
+    }
+    Some(format!(
+        r#"Caution: crate "{}" links to a repository for "{}" ({})"#,
+        info.name, project, repository
+    ))
+}
+
+/// Note for when crates.io knows the crate by a different name than the one asked for,
+/// like a different case or `-` for `_`, or an old name it redirects.
+fn canonical_name_notice(requested: &str, info: &CrateInfo) -> Option<String> {
+    if requested == info.name {
+        return None;
+    }
+    Some(format!(
+        r#""{}" is published as "{}"; using that name"#,
+        requested, info.name
+    ))
+}
+
+/// Resolve a link the same way as when navigating, explaining each step.
+fn explain_resolution(
+    client: &LazyClient,
+    crate_name: &str,
+    destination: Option<Destination>,
+    config: &Config,
+    opt: &Options,
+) -> Trace {
+    let mut trace = Trace::default();
+    match trace_resolution(client, crate_name, destination, config, opt, &mut trace) {
+        Ok(Some(url)) => trace.step("final URL", url),
+        Ok(None) => (),
+        Err(e) => trace.step("result", format!("error: {}", e)),
+    }
+    trace
+}
+
+/// Crate names for `--batch`, from the file at `path`, or stdin for "-".
+fn read_batch(path: &str) -> Result<Vec<String>> {
+    if path == "-" {
+        return batch::read_names(io::stdin().lock());
+    }
+    batch::read_names(io::BufReader::new(File::open(path)?))
+}
+
+/// Sort looked-up crates, putting those that couldn't be found last.
+fn sort_crates(crates: &mut [(String, Result<CrateInfo>)], order: CrateOrder) {
+    crates.sort_by(|(_, a), (_, b)| match (a, b) {
+        (Ok(a), Ok(b)) => match order {
+            CrateOrder::Name => a.name.cmp(&b.name),
+            CrateOrder::Downloads => b.downloads.cmp(&a.downloads),
+            // crates.io timestamps are all UTC RFC 3339, so they sort as text
+            CrateOrder::Updated => b.updated_at.cmp(&a.updated_at),
+        },
+        (a, b) => b.is_ok().cmp(&a.is_ok()),
+    });
+}
+
+/// Look up each crate, `jobs` at a time, and its link to the destination.
+///
+/// The crates keep their order unless an `order` is given.
+fn batch_links(
+    client: &Client,
+    names: &[String],
+    destination: &Destination,
+    config: &Config,
+    order: Option<CrateOrder>,
+    jobs: usize,
+) -> Vec<(String, Result<String>)> {
+    let infos = batch::fetch_all(names, jobs, |name| get_crate_info(client, name));
+    let mut found = names.iter().cloned().zip(infos).collect::<Vec<_>>();
+    if let Some(order) = order {
+        sort_crates(&mut found, order);
+    }
+    found
+        .into_iter()
+        .map(|(name, info)| {
+            let link = info.and_then(|i| determine_link(&i, destination, config));
+            (name, link)
+        })
+        .collect()
+}
+
+/// Go through the steps of navigating to the crate without opening anything,
+/// returning the link unless the crate is handled locally.
+fn trace_resolution(
+    client: &LazyClient,
+    crate_name: &str,
+    destination: Option<Destination>,
+    config: &Config,
+    opt: &Options,
+    trace: &mut Trace,
+) -> Result<Option<String>> {
+    trace.step("crate name", crate_name);
+    let lookup = lookup_name(crate_name, opt.exact);
+    trace.step(
+        "lookup name",
+        if opt.exact {
+            format!("{} (used as given with --exact)", lookup)
+        } else if lookup != crate_name {
+            format!("{} (lowercased)", lookup)
+        } else {
+            lookup.clone()
+        },
+    );
+    let configured = config
+        .crate_default(&lookup)
+        .map(parse_destination)
+        .transpose()?;
+    let destination = choose_destination(
+        destination,
+        configured,
+        opt.no_default_crate_page,
+        &mut Prompt::new(io::empty(), false, opt.assume_yes),
+        trace,
+    )?;
+    if opt.local_docs && matches!(destination, Destination::D | Destination::Documentation) {
+        trace.step("result", "documentation built locally (--local-docs)");
+        return Ok(None);
+    }
+    let (patch, local) = project_source(crate_name, opt.exact, true, trace);
+    if let Some(dir) = local {
+        trace.step("result", format!("local crate at {}", dir.display()));
+        return Ok(None);
+    }
+    let mut info = lookup_crate_info(client, &lookup, opt, trace)?;
+    apply_git_patch(&mut info, patch.as_ref(), trace);
+    if info.name != lookup {
+        trace.step("canonical name", &info.name);
+    }
+    trace.step("candidate links", info.candidate_links());
+    if destination.needs_owners() {
+        info.owners = get_owners(client.get(), &info.name)?;
+        let logins = info
+            .owners
+            .iter()
+            .map(|o| o.login.as_str())
+            .collect::<Vec<_>>();
+        trace.step("owners", logins.join(", "));
+    }
+    if checks_renames(opt, &destination) {
+        follow_repository_rename(client, &mut info, trace);
+    }
+    resolve_link(&info, &destination, config, trace).map(Some)
+}
+
+/// Remove the subcommand name cargo inserts when running as `cargo nav`.
+///
+/// Cargo runs external subcommands as `cargo-nav nav <args>`, with the `CARGO`
+/// environment variable set, so the first argument after the binary is only
+/// dropped when it's exactly "nav", the binary is `cargo-nav`, and it was run
+/// `via_cargo`. Anything after that, including a crate named "nav" or flags, is
+/// left for argument parsing, as is everything when run directly.
+///
+/// Some launchers pass a missing or empty program name; that's replaced with
+/// `cargo-nav` so it isn't mistaken for an argument and help output reads right.
+fn normalize_args(mut args: Vec<String>, via_cargo: bool) -> Vec<String> {
+    match args.first() {
+        None => args.push(String::from("cargo-nav")),
+        Some(a) if a.is_empty() => args[0] = String::from("cargo-nav"),
+        Some(_) => (),
+    }
+    let is_cargo_nav = args
+        .first()
+        .and_then(|a| Path::new(a).file_stem())
+        .is_some_and(|stem| stem == "cargo-nav");
+    if via_cargo && is_cargo_nav && args.get(1).is_some_and(|a| a == "nav") {
+        args.remove(1);
+    }
+    args
+}
+
+/// Run a `config` subcommand.
+fn run_config_command(command: &ConfigCommand, environment: &Environment) -> Result<()> {
+    let path = config::config_path().ok_or_else(|| anyhow!("No config directory found"))?;
+    match command {
+        ConfigCommand::Set { key, value } => {
+            config::set(&path, key, value)?;
+            eprintln!("Set {} in {}", key, path.display());
+        }
+        ConfigCommand::Get { key } => {
+            output::print(&Config::load(environment)?.get(key)?.unwrap_or_default());
+        }
+        ConfigCommand::Reset => {
+            if config::reset(&path)? {
+                eprintln!("Deleted {}", path.display());
+            } else {
+                eprintln!("There's no config file at {}", path.display());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Entrypoint.
+fn main() {
+    let start = Instant::now();
+    let args = normalize_args(env::args().collect(), env::var_os("CARGO").is_some());
+    let mut opt = Options::from_iter(args.iter());
+    let timings = Timings::new(opt.timings, start);
+    timings.finish("arg parse");
+    let environment = Environment::new(!opt.no_env);
+    // before loading the config, so a broken one can be fixed
+    if let Some(Command::Config(command)) = &opt.command {
+        if let Err(e) = run_config_command(command, &environment) {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+        return;
+    }
+    let mut config = match Config::load(&environment) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Error setting up: {}", e);
+            process::exit(1);
+        }
+    };
+    let flag_level = opt.log_level.or(if opt.debug {
+        Some(LevelFilter::Debug)
+    } else {
+        None
+    });
+    if let Some(level) = flag_level {
+        config.log_level = Some(level.to_string().to_lowercase());
+        config.set_by_flag("log_level");
+    }
+    if let Some(cmd) = &opt.pre_open_cmd {
+        config.pre_open_cmd = Some(cmd.clone());
+        config.set_by_flag("pre_open_cmd");
+    }
+    if opt.no_pager {
+        config.no_pager = true;
+        config.set_by_flag("no_pager");
+    }
+    if opt.record_stats {
+        config.record_stats = true;
+        config.set_by_flag("record_stats");
+    }
+    if let Some(url) = &opt.web_url {
+        config.web_base = Some(url.clone());
+        config.set_by_flag("web_base");
+    }
+    let levels = log_levels(flag_level, &config);
+    let color = color::use_color(opt.color, &environment, atty::is(atty::Stream::Stdout));
+    // with --json, --print, and --print-json-field, stdout is only the result
+    let machine_output = opt.json || opt.print || opt.print0 || opt.print_json_field.is_some();
+    if let Err(e) = levels.and_then(|l| setup_logging(&l, color, machine_output)) {
+        eprintln!("Error setting up: {}", e);
+        process::exit(1);
+    }
+    debug!("CLI options: {:?}", opt);
+    debug!("Config: {:?}", config);
+    timings.finish("config");
+    if opt.build_info {
+        output::print(&build_info());
+        return;
+    }
+    opt.print |= opt.print0;
+    let terminator = if opt.print0 {
+        Terminator::Nul
+    } else {
+        Terminator::Newline
+    };
+    if opt.paths {
+        output::print(&config.report(config::config_path().as_deref()));
+        return;
+    }
+    let opener = Opener::new(opt.opener_cmd.clone(), opt.wait);
+    let session = opt.record_session.as_deref();
+    if let Some(path) = &opt.replay_session {
+        let limit = opt.open_limit_host;
+        if let Err(e) = replay_session(path, limit, &config, &opener, session, opt.full_urls) {
+            error!("{}", e);
+            process::exit(1);
+        }
+        return;
+    }
+    if opt.stats {
+        if let Err(e) = print_stats(10) {
+            error!("{}", e);
+            process::exit(1);
+        }
+        return;
+    }
+    if opt.ignore_tls_errors {
+        eprintln!(
+            "WARNING: --ignore-tls-errors is set, so TLS certificate validation is DISABLED \
+             (danger_accept_invalid_certs). Anyone between you and the registry can read \
+             and alter responses. Only use this behind a proxy you trust."
+        );
+    }
+    let client = LazyClient::new(ClientOptions::from_options(&opt, &environment), &timings);
+    if let Some(port) = opt.serve {
+        let resolve = |name: &str, destination: Option<&str>| {
+            served_link(client.get(), &config, name, destination)
+        };
+        let served = TcpListener::bind(("127.0.0.1", port))
+            .and_then(|listener| serve::serve(listener, &resolve));
+        if let Err(e) = served {
+            error!("Could not serve on port {}: {}", port, e);
+            process::exit(1);
+        }
+        return;
+    }
+    let pager = Pager::new(
+        atty::is(atty::Stream::Stdout)
+            && !config.no_pager
+            && !opt.json
+            && !opt.print
+            && opt.emit_to.is_none(),
+        &environment,
+    );
+    match opt.command.take() {
+        Some(Command::Resolve {
+            crate_name,
+            destination,
+        }) => {
+            output::print(
+                &explain_resolution(&client, &crate_name, destination, &config, &opt).to_string(),
+            );
+            return;
+        }
+        Some(Command::AuditLinks { crate_name, output }) => {
+            let info = match get_crate_info(client.get(), &lookup_name(&crate_name, false)) {
+                Ok(i) => i,
+                Err(e) => {
+                    error!(
+                        r#"Could not find crate information for "{}": {}"#,
+                        crate_name, e
+                    );
+                    process::exit(1);
+                }
+            };
+            let links = info.links();
+            if links.is_empty() {
+                info!("{} has no links to check", info.name);
+                return;
+            }
+            let checks = audit::check_links(client.get(), &links);
+            output::print(&audit::report(&checks, color, opt.full_urls));
+            if let Some(path) = output {
+                if let Err(e) = audit::write_json(&path, &checks) {
+                    error!("Could not write to {}: {}", path.display(), e);
+                    process::exit(1);
+                }
+            }
+            if !checks.iter().all(audit::LinkCheck::healthy) {
+                process::exit(1);
+            }
+            return;
+        }
+        Some(Command::Author { login, open }) => {
+            let crates = match get_author_crates(client.get(), &login) {
+                Ok(c) => c,
+                Err(e) => {
+                    error!(r#"Could not list the crates of "{}": {}"#, login, e);
+                    process::exit(1);
+                }
+            };
+            let number = match open {
+                Some(n) => n,
+                None => {
+                    if crates.is_empty() {
+                        info!("{} hasn't published any crates", login);
+                    } else {
+                        let width = opt.desc_width.or_else(pager::terminal_width);
+                        show_long(&pager, &crates_table(&crates, width));
+                    }
+                    return;
+                }
+            };
+            let chosen = match number.checked_sub(1).and_then(|i| crates.get(i)) {
+                Some(c) => c,
+                None => {
+                    error!(
+                        "{} has {} crates; there's no number {}",
+                        login,
+                        crates.len(),
+                        number
+                    );
+                    process::exit(1);
+                }
+            };
+            let url = config.crate_page(&chosen.name);
+            if opt.print {
+                output::print_link(&url, terminator);
+                return;
+            }
+            let link = SessionRecord::new(&chosen.name, Destination::C.label(), &url);
+            if let Err(e) = open_link(&link, &config, &opener, session, opt.full_urls) {
+                error!("{}", e);
+                process::exit(1);
+            }
+            return;
+        }
+        Some(Command::Stats { limit }) => {
+            if let Err(e) = print_stats(limit) {
+                error!("{}", e);
+                process::exit(1);
+            }
+            return;
+        }
+        Some(Command::Config(_)) | None => (),
+    }
+    let crate_name = match opt.crate_name.take() {
+        Some(n) => n,
+        None => ClapError::with_description(
+            "The crate name argument is required",
+            ErrorKind::MissingRequiredArgument,
+        )
+        .exit(),
+    };
+    if opt.search {
+        let results = match search_crates(client.get(), &crate_name) {
+            Ok(r) => r,
+            Err(e) => {
+                error!("Could not search crates.io: {}", e);
+                process::exit(1);
+            }
+        };
+        if !opt.exact_match_only {
+            for found in &results.crates {
+                info!("{}", found.name);
+            }
+            if opt.count {
+                info!("{}", results.summary());
+            }
+            return;
+        }
+        if let Err(e) = results.exact_match(&crate_name) {
+            error!("{}", e);
+            process::exit(1);
+        }
+    }
+    let crate_name = match split_crate_version(&crate_name, opt.crate_version.take()) {
+        Ok((name, version)) => {
+            opt.crate_version = version;
+            name
+        }
+        Err(e) => ClapError::with_description(&e.to_string(), ErrorKind::ValueValidation).exit(),
+    };
+    if opt.strict_semver {
+        let versions = [&opt.crate_version, &opt.from_version, &opt.to_version];
+        for version in versions.iter().filter_map(|v| v.as_deref()) {
+            if let Err(e) = check_strict_version(version) {
+                ClapError::with_description(&e.to_string(), ErrorKind::ValueValidation).exit();
+            }
+        }
+    }
+    if opt.no_version_check {
+        if let Some(version) = opt.crate_version.take() {
+            warn!(
+                "Using the latest version instead of {} because of --no-version-check",
+                version
+            );
+        }
+    }
+    if let Some(seconds) = opt.watch {
+        watch(
+            client.get(),
+            &lookup_name(&crate_name, opt.exact),
+            Duration::from_secs(seconds.max(1)),
+        );
+    }
+    if opt.raw_response {
+        match get_raw_response(client.get(), &lookup_name(&crate_name, opt.exact)) {
+            Ok(body) => output::print(&body),
+            Err(e) => {
+                error!("{}", e);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+    if opt.all_registries {
+        if config.registries.is_empty() {
+            warn!("No registries are configured, so only crates.io is used");
+        }
+        let name = lookup_name(&crate_name, opt.exact);
+        let pages = config.registry_pages(&name);
+        if opt.print {
+            for (_, url) in &pages {
+                output::print_link(url, terminator);
+            }
+            return;
+        }
+        for (registry, url) in &pages {
+            info!("{}: {}", registry, display::url(url, opt.full_urls));
+            let link = SessionRecord::new(&name, &format!("crate ({})", registry), url);
+            if let Err(e) = open_link(&link, &config, &opener, session, opt.full_urls) {
+                error!("{}", e);
+            }
+        }
+        return;
+    }
+    let interactive = atty::is(atty::Stream::Stdin) && atty::is(atty::Stream::Stdout);
+    let configured = config
+        .crate_default(&lookup_name(&crate_name, opt.exact))
+        .map(parse_destination)
+        .transpose();
+    let configured = match configured {
+        Ok(d) => d,
+        Err(e) => {
+            error!(
+                "Invalid destination for {} in crate_defaults: {}",
+                crate_name, e
+            );
+            process::exit(1);
+        }
+    };
+    let destination = match choose_destination(
+        opt.destination.take(),
+        configured,
+        opt.no_default_crate_page,
+        &mut Prompt::new(io::stdin().lock(), interactive, opt.assume_yes),
+        &mut Trace::default(),
+    ) {
+        Ok(d) => d,
+        Err(e) => {
+            error!("{}", e);
+            process::exit(1);
+        }
+    };
+    if opt.batch {
+        let names = match read_batch(&crate_name) {
+            Ok(n) => n,
+            Err(e) => {
+                error!("Could not read crate names from {}: {}", crate_name, e);
+                process::exit(1);
+            }
+        };
+        let jobs = opt.jobs.unwrap_or_else(batch::default_jobs);
+        let mut failed = false;
+        for (name, link) in
+            batch_links(client.get(), &names, &destination, &config, opt.order, jobs)
+        {
+            match link {
+                Ok(url) => output::print(&format!("{}: {}", name, url)),
+                Err(e) => {
+                    error!("{}: {}", name, e);
+                    failed = true;
+                }
+            }
+        }
+        if failed {
+            process::exit(1);
+        }
+        return;
+    }
+    let local_docs =
+        opt.local_docs && matches!(destination, Destination::D | Destination::Documentation);
+    let (patch, local) = project_source(&crate_name, opt.exact, !local_docs, &mut Trace::default());
+    if let Some(p) = &patch {
+        info!("{}", p.notice(&crate_name));
+    }
+    if let Some(dir) = local {
+        if let Err(e) = navigate_path_dependency(
+            &crate_name,
+            &dir,
+            &destination,
+            opt.print.then_some(terminator),
+            opt.build_docs,
+        ) {
+            error!("{}", e);
+            process::exit(1);
+        }
+        timings.finish("resolve");
+        return;
+    }
+    let mut info = if local_docs {
+        let link = env::current_dir()
+            .map_err(anyhow::Error::from)
+            .and_then(|dir| local_docs_link(&crate_name, &target_dirs(&dir, &environment)));
+        match link {
+            Ok(url) => CrateInfo {
+                name: crate_name.clone(),
+                documentation: Some(url),
+                ..CrateInfo::default()
+            },
+            Err(e) => {
+                error!("{}", e);
+                process::exit(1);
+            }
+        }
+    } else {
+        let lookup = lookup_name(&crate_name, opt.exact);
+        match lookup_crate_info(&client, &lookup, &opt, &mut Trace::default()) {
+            Ok(i) => {
+                debug!("API info: {:?}", i);
+                timings.finish("network");
+                i
+            }
+            Err(e) => {
+                debug!("Error getting crate info: {}", e);
+                if opt.json || opt.emit_to.is_some() {
+                    print_json(
+                        &JsonOutput::not_found(&crate_name, &e),
+                        opt.format_json_array,
+                        opt.emit_to.as_deref(),
+                    );
+                    return;
+                }
+                error!(r#"Could not find crate information for "{}""#, crate_name);
+                if opt.diagnose {
+                    report_diagnosis(&e);
+                }
+                process::exit(1);
+            }
+        }
+    };
+    apply_git_patch(&mut info, patch.as_ref(), &mut Trace::default());
+    if !opt.quiet {
+        if let Some(notice) = canonical_name_notice(&crate_name, &info) {
+            info!("{}", notice);
+        }
+        if let Some(caution) = repository_mismatch(&info) {
+            warn!("{}", caution);
+        }
+    }
+    if opt.deprecated_check {
+        if let Some(marker) = deprecation::marker(info.description.as_deref(), &info.keywords) {
+            warn!("{} may be deprecated ({})", info.name, marker);
+        }
+    }
+    if let Destination::Versions = destination {
+        match recent_versions(client.get(), &info.name, opt.limit, opt.no_version_check) {
+            Ok(Some(listing)) => show_long(&pager, &listing),
+            Ok(None) => (),
+            Err(e) => {
+                error!("Could not get the versions of {}: {}", info.name, e);
+                process::exit(1);
+            }
+        }
+    }
+    if destination.needs_owners() {
+        match get_owners(client.get(), &info.name) {
+            Ok(owners) => info.owners = owners,
+            Err(e) => {
+                error!("Could not get the owners of {}: {}", info.name, e);
+                process::exit(1);
+            }
+        }
+    }
+    if let Some(field) = &opt.print_json_field {
+        output::print(&json_field(&info, field, &config).unwrap_or_default());
+        return;
+    }
+    if let Some(date) = opt.open_if_updated_after {
+        match updated_since(&info, date) {
+            Ok(true) => (),
+            Ok(false) => {
+                if opt.quiet_skip {
+                    info!("skipped: not updated");
+                }
+                return;
+            }
+            Err(e) => {
+                error!("{}", e);
+                process::exit(1);
+            }
+        }
+    }
+    if opt.info {
+        let trend = optional_download_trend(client.get(), &info.name, opt.offline);
+        show_long(
+            &pager,
+            &format!(
+                "{}\n{}",
+                info_report(
+                    &info,
+                    &config,
+                    opt.crate_version.as_deref(),
+                    opt.desc_width.or_else(pager::terminal_width)
+                ),
+                downloads_report(&info, trend.as_ref())
+            ),
+        );
+        if opt.du && opt.no_version_check {
+            info!("Not checking the download size with --no-version-check");
+        } else if opt.du {
+            match info.version(opt.crate_version.as_deref()) {
+                Some(v) => match download_size(client.get(), &info.name, &v.num) {
+                    Ok(size) => info!("Download size: {}", format_size(size)),
+                    Err(e) => error!("Could not get the download size: {}", e),
+                },
+                None => error!("No version to check the download size of"),
+            }
+        }
+        return;
+    }
+    if opt.all_in_one {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let path = match html::write(&info.name, &all_links(&info, &config), timestamp) {
+            Ok(p) => p,
+            Err(e) => {
+                error!("Could not write the links page: {}", e);
+                process::exit(1);
+            }
+        };
+        if opt.print {
+            output::print_link(&path.display().to_string(), terminator);
+            return;
+        }
+        let url = format!("file://{}", path.display());
+        let link = SessionRecord::new(&info.name, "all-in-one", &url);
+        if let Err(e) = open_link(&link, &config, &opener, session, opt.full_urls) {
+            error!("{}", e);
+            process::exit(1);
+        }
+        return;
+    }
+    if checks_renames(&opt, &destination) {
+        if let Some(old) = follow_repository_rename(&client, &mut info, &mut Trace::default()) {
+            if !opt.quiet {
+                warn!(
+                    "The repository {} has moved to {}; using that instead",
+                    old,
+                    info.repository.as_deref().unwrap_or_default()
+                );
+            }
+        }
+    }
+    if opt.infer_homepage
+        && info.homepage.is_none()
+        && matches!(destination, Destination::H | Destination::Homepage)
+    {
+        match inferred_homepage(client.get(), &info) {
+            Ok(url) => {
+                info!("The homepage isn't set; using {} from the README", url);
+                info.homepage = Some(url);
+            }
+            Err(e) => debug!("Could not infer the homepage: {}", e),
+        }
+    }
+    let link = if opt.community {
+        community_link(client.get(), &info)
+    } else if let Destination::Team = destination {
+        team_link(
+            &info,
+            &config,
+            &mut Prompt::new(io::stdin().lock(), interactive, opt.assume_yes),
+        )
+    } else if let Destination::Compare = destination {
+        compare_link(
+            &info,
+            opt.from_version.as_deref(),
+            opt.to_version.as_deref(),
+        )
+    } else if let Destination::Archive = destination {
+        archive_link(&info, opt.crate_version.as_deref())
+    } else if let Destination::ArchivedVersion = destination {
+        archived_version_link(&info, opt.crate_version.as_deref(), &config)
+    } else if opt.open_repo_tree_at_version {
+        repo_tree_link(&info, opt.crate_version.as_deref())
+    } else if !opt.features.is_empty()
+        && matches!(destination, Destination::D | Destination::Documentation)
+    {
+        Ok(features_link(
+            &info,
+            &opt.features,
+            opt.crate_version.as_deref(),
+        ))
+    } else {
+        determine_link(&info, &destination, &config)
+    };
+    timings.finish("resolve");
+    if opt.open_audit_command {
+        output::print(&command::audit(&info.name));
+        if !matches!(destination, Destination::Advisory) {
+            return;
+        }
+    }
+    if opt.json || opt.emit_to.is_some() {
+        let trend = optional_download_trend(client.get(), &info.name, opt.offline);
+        print_json(
+            &JsonOutput::found(&info, opt.crate_version.as_deref(), &link)
+                .with_trend(trend.as_ref()),
+            opt.format_json_array,
+            opt.emit_to.as_deref(),
+        );
+        return;
+    }
+    let url = match link {
+        Ok(u) => u,
+        Err(e) if opt.no_open_on_missing => {
+            info!("{}", missing_link_listing(&info, &config, &e));
+            return;
+        }
+        Err(e) => {
+            error!("Error determining link: {}", e);
+            info!(
+                "Here is the info that was found: {}",
+                info.describe(&config)
+            );
+            process::exit(1);
+        }
+    };
+    let url = match opt.theme {
+        Some(theme) => with_docs_theme(&url, theme),
+        None => url,
+    };
+    if opt.only_if_documented
+        && !local_docs
+        && matches!(destination, Destination::D | Destination::Documentation)
+    {
+        if let Err(e) = check_documented(client.get(), &info.name, opt.crate_version.as_deref()) {
+            error!("{}", e);
+            process::exit(1);
+        }
+    }
+    if opt.open_pr_count && matches!(destination, Destination::PullRequests) {
+        let github_token = opt
+            .github_token
+            .clone()
+            .or_else(|| environment.var(environment::GITHUB_TOKEN).map(GithubToken));
+        match (&github_token, &info.repository) {
+            (Some(token), Some(repo)) => match open_pr_count(client.get(), repo, token) {
+                Ok(count) => info!("{} open pull requests", count),
+                Err(e) => debug!("Error getting the open pull request count: {}", e),
+            },
+            _ => debug!("No GitHub token set; skipping the open pull request count"),
+        }
+    }
+    if opt.check {
+        let checker = if opt.no_follow_redirect {
+            client.no_redirects()
+        } else {
+            client.get()
+        };
+        match check_link(checker, &url) {
+            Ok(status) if status.is_success() || status.is_redirection() => {
+                info!("{} responded with {}", url, status)
+            }
+            Ok(status) => {
+                error!("{} responded with {}", url, status);
+                process::exit(1);
+            }
+            Err(e) => {
+                error!("Could not check {}: {}", url, e);
+                process::exit(1);
+            }
+        }
+    }
+    if let Destination::Settings = destination {
+        info!("The settings page requires being logged in to crates.io as an owner of the crate");
+    }
+    if opt.copy_markdown {
+        let link = markdown_link(&info.name, &url);
+        if let Err(e) = copy_to_clipboard(&link) {
+            error!("Could not copy to the clipboard: {}", e);
+            process::exit(1);
+        }
+        output::print(&link);
+        return;
+    }
+    if opt.download {
+        if !matches!(destination, Destination::Archive) {
+            error!("--download only works with the archive destination");
+            process::exit(1);
+        }
+        let size = info
+            .version(opt.crate_version.as_deref().or(info.max_version.as_deref()))
+            .and_then(|v| v.crate_size);
+        match size {
+            Some(s) => info!("Downloading {} ({})", url, format_size(s)),
+            None => info!("Downloading {}", url),
+        }
+        let saved = env::current_dir()
+            .map_err(anyhow::Error::from)
+            .and_then(|dir| download_archive(client.get(), &url, &dir));
+        match saved {
+            Ok(path) => info!("Saved {}", path.display()),
+            Err(e) => {
+                error!("Could not download the archive: {}", e);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+    let dependency_urls = if opt.depth > 0 {
+        match dependency_links(
+            client.get(),
+            &info,
+            &destination,
+            &config,
+            opt.depth,
+            opt.max_tabs,
+            opt.jobs.unwrap_or_else(batch::default_jobs),
+        ) {
+            Ok(urls) => urls,
+            Err(e) => {
+                error!("Could not look up the dependencies: {}", e);
+                process::exit(1);
+            }
+        }
+    } else {
+        Vec::new()
+    };
+    if opt.print {
+        output::print_link(&url, terminator);
+        for (_, url) in &dependency_urls {
+            output::print_link(url, terminator);
+        }
+        timings.finish("open");
+        return;
+    }
+    debug!("URL to open: {}", url);
+    let link = SessionRecord::new(&info.name, destination.label(), &url);
+    if let Err(e) = open_link(&link, &config, &opener, session, opt.full_urls) {
+        error!("{}", e);
+        process::exit(1);
+    }
+    for (name, url) in &dependency_urls {
+        let link = SessionRecord::new(name, destination.label(), url);
+        if let Err(e) = open_link(&link, &config, &opener, session, opt.full_urls) {
+            error!("{}", e);
+        }
+    }
+    timings.finish("open");
+    if config.record_stats {
+        if let Err(e) = record_stats(&info.name, &destination) {
+            debug!("Error recording stats: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        all_links, archive_link, archived_version_link, batch_links, build_client, build_info,
+        canonical_name_notice, check_documented, check_link, check_strict_version, checks_renames,
+        choose_destination, collect_dependencies, community_link, compare_link, config::Config,
+        crates_table, days_since_epoch, determine_link, download_archive, download_size,
+        downloads_report, edit_distance, environment, explain_resolution, external_crate_info,
+        features_link, first_available, format_size, get_author_crates, get_crate_info,
+        get_download_trend, get_owners, get_raw_response, get_versions, git_dependency_info,
+        index_path, inferred_homepage, info_report, is_not_found, json_field, json_text,
+        lenient_crate_info, limit_per_host, local_docs_link, local_docs_path, log_levels,
+        lookup_name, markdown_link, match_name, missing_link_listing, moved_repository,
+        newest_version, normalize_args, open_link, open_pr_count, optional_download_trend,
+        parse_api_response, parse_destination, parse_header, parse_tls_version,
+        path_dependency_dir, project_patch, recent_versions, replay_session, repo_tree_link,
+        repository_mismatch, search_crates, serve, served_link, session, similar_crates_link,
+        sort_crates, split_crate_version, target_dirs, team_link, team_url, truncate_to_width,
+        updated_since, urls, version_change, versions_listing, with_docs_theme, ClientOptions,
+        ColorChoice, Command, ConfigCommand, CrateInfo, CrateInfoWrapper, CrateOrder, Destination,
+        DocsTheme, DownloadBucket, DownloadTrend, Environment, GithubToken, Instant, JsonOutput,
+        LazyClient, LogLevels, Opener, Options, Owner, Prompt, SessionRecord, Timings, Trace,
+        JSON_FIELDS,
+    };
+    use anyhow::{anyhow, Result};
+    use chrono::NaiveDate;
+    use log::LevelFilter;
+    use mockito::mock;
+    use reqwest::{blocking::Client, tls};
+    use serde_json::Value;
+    use std::{
+        env, fs,
+        io::{Cursor, Read, Write},
+        net::{TcpListener, TcpStream},
+        path::Path,
+        thread,
+    };
+    use structopt::{clap::ErrorKind, StructOpt};
+
+    fn client() -> Client {
+        build_client(&ClientOptions::default()).unwrap()
+    }
+
+    fn explain(crate_name: &str, destination: Destination) -> String {
+        let timings = Timings::new(false, Instant::now());
+        let client = LazyClient::new(ClientOptions::default(), &timings);
+        let opt = Options::from_iter(&["cargo-nav", crate_name]);
+        explain_resolution(
+            &client,
+            crate_name,
+            Some(destination),
+            &Config::default(),
+            &opt,
+        )
+        .to_string()
+    }
+
+    fn crate_info() -> CrateInfo {
+        CrateInfo {
+            name: "a".to_owned(),
+            homepage: Some("b".to_owned()),
+            documentation: Some("c".to_owned()),
+            ..CrateInfo::default()
+        }
+    }
+
+    #[test]
+    fn test_determine_link_short() {
+        let url = determine_link(&crate_info(), &Destination::D, &Config::default()).unwrap();
+        assert_eq!(url, "c");
+    }
+
+    #[test]
+    fn test_determine_link_long() {
+        let url =
+            determine_link(&crate_info(), &Destination::Homepage, &Config::default()).unwrap();
+        assert_eq!(url, "b");
+    }
+
+    #[test]
+    fn determine_link_missing() {
+        let result = determine_link(&crate_info(), &Destination::Repository, &Config::default());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_determine_link_crate() {
+        let url = determine_link(&crate_info(), &Destination::C, &Config::default()).unwrap();
+        assert_eq!(url, "https://crates.io/crates/a");
+    }
+
+    #[test]
+    fn test_determine_link_crate_frontend() {
+        let config = Config::from_toml(r#"frontend = "https://crates.live/{name}""#).unwrap();
+        let url = determine_link(&crate_info(), &Destination::Crate, &config).unwrap();
+        assert_eq!(url, "https://crates.live/a");
+        assert_eq!(
+            json_field(&crate_info(), "crate", &config).as_deref(),
+            Some("https://crates.live/a")
+        );
+    }
+
+    #[test]
+    fn test_determine_link_crate_web_base() {
+        let config = Config::from_toml(r#"web_base = "https://mirror.example.com/""#).unwrap();
+        let url = determine_link(&crate_info(), &Destination::Crate, &config).unwrap();
+        assert_eq!(url, "https://mirror.example.com/crates/a");
+    }
+
+    #[test]
+    fn test_determine_link_web_url() {
+        let mut config =
+            Config::from_toml(r#"web_url = "https://crates.internal.example/""#).unwrap();
+        let info = CrateInfo {
+            name: "a".to_owned(),
+            max_version: Some("1.2.0".to_owned()),
+            categories: vec!["parsing".to_owned()],
+            owners: vec![owner("alice", "user"), owner("github:acme:core", "team")],
+            ..CrateInfo::default()
+        };
+        let expected = [
+            (Destination::Crate, "/crates/a"),
+            (Destination::Settings, "/crates/a/settings"),
+            (Destination::Versions, "/crates/a/versions"),
+            (Destination::SimilarCrates, "/categories/parsing"),
+            (Destination::ArchivedVersion, "/crates/a/1.2.0"),
+            (Destination::OwnerProfile, "/users/alice"),
+            (Destination::Team, "/teams/github:acme:core"),
+        ];
+        for base in &[
+            "https://crates.internal.example",
+            "https://mirror.example.com",
+        ] {
+            for (destination, path) in &expected {
+                let url = determine_link(&info, destination, &config).unwrap();
+                assert_eq!(url, format!("{}{}", base, path), "{:?}", destination);
+            }
+            config.web_base = Some("https://mirror.example.com/".to_owned());
+        }
+    }
+
+    #[test]
+    fn test_determine_link_feature_flags() {
+        let url = determine_link(
+            &crate_info(),
+            &Destination::FeatureFlags,
+            &Config::default(),
+        )
+        .unwrap();
+        assert_eq!(url, "https://featureflags.dev/crates/a");
+    }
+
+    #[test]
+    fn test_determine_link_reproducible_builds() {
+        let destination = parse_destination("reproduciblebuilds").unwrap();
+        assert!(matches!(destination, Destination::ReproducibleBuilds));
+        let url = determine_link(&crate_info(), &destination, &Config::default()).unwrap();
+        assert_eq!(url, "https://reproducible.rs/crates/a");
+    }
+
+    #[test]
+    fn test_determine_link_settings() {
+        let url =
+            determine_link(&crate_info(), &Destination::Settings, &Config::default()).unwrap();
+        assert_eq!(url, "https://crates.io/crates/a/settings");
+    }
+
+    #[test]
+    fn test_repo_tree_link() {
+        let mut info = crate_info();
+        assert!(repo_tree_link(&info, Some("1.0.0")).is_err());
+        info.repository = Some("https://github.com/a/a".to_owned());
+        assert!(repo_tree_link(&info, None).is_err());
+        assert_eq!(
+            repo_tree_link(&info, Some("1.0.0")).unwrap(),
+            "https://github.com/a/a/tree/v1.0.0"
+        );
+    }
+
+    #[test]
+    fn test_compare_link() {
+        let mut info = crate_info();
+        assert!(compare_link(&info, Some("1.0.0"), None).is_err());
+        info.repository = Some("https://github.com/a/a".to_owned());
+        assert!(compare_link(&info, None, None).is_err());
+        assert_eq!(
+            compare_link(&info, Some("1.0.0"), Some("1.0.1")).unwrap(),
+            "https://github.com/a/a/compare/v1.0.0...v1.0.1"
+        );
+        assert_eq!(
+            compare_link(&info, None, Some("1.0.1")).unwrap(),
+            "https://github.com/a/a/compare/v1.0.1...HEAD"
+        );
+    }
+
+    #[test]
+    fn test_repository_mismatch() {
+        let mut info = crate_info();
+        assert_eq!(repository_mismatch(&info), None);
+        info.name = "tokio-util".to_owned();
+        info.repository = Some("https://github.com/tokio-rs/tokio".to_owned());
+        assert_eq!(repository_mismatch(&info), None);
+        info.name = "serde-derive-utils".to_owned();
+        info.repository = Some("https://github.com/serde-rs/json".to_owned());
+        assert_eq!(
+            repository_mismatch(&info).as_deref(),
+            Some(
+                r#"Caution: crate "serde-derive-utils" links to a repository for "json" (https://github.com/serde-rs/json)"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_determine_link_security() {
+        let mut info = crate_info();
+        assert!(determine_link(&info, &Destination::Security, &Config::default()).is_err());
+        info.repository = Some("https://github.com/a/a".to_owned());
+        let url = determine_link(&info, &Destination::Security, &Config::default()).unwrap();
+        assert_eq!(url, "https://github.com/a/a/security");
+        info.repository = Some("https://gitlab.com/a/a".to_owned());
+        let result = determine_link(&info, &Destination::Security, &Config::default());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_determine_link_coverage() {
+        let mut info = crate_info();
+        assert!(determine_link(&info, &Destination::Coverage, &Config::default()).is_err());
+        info.repository = Some("https://github.com/a/a".to_owned());
+        let url = determine_link(&info, &Destination::Coverage, &Config::default()).unwrap();
+        assert_eq!(url, "https://codecov.io/gh/a/a");
+        info.repository = Some("https://gitlab.com/a/a".to_owned());
+        let url = determine_link(&info, &Destination::Coverage, &Config::default()).unwrap();
+        assert_eq!(url, "https://coveralls.io/gitlab/a/a");
+    }
+
+    #[test]
+    fn test_determine_link_advisory() {
+        let url =
+            determine_link(&crate_info(), &Destination::Advisory, &Config::default()).unwrap();
+        assert_eq!(url, "https://rustsec.org/packages/a.html");
+    }
+
+    #[test]
+    fn test_determine_link_pull_requests() {
+        let mut info = crate_info();
+        info.repository = Some("https://github.com/a/a.git".to_owned());
+        let url = determine_link(&info, &Destination::PullRequests, &Config::default()).unwrap();
+        assert_eq!(url, "https://github.com/a/a/pulls");
+    }
+
+    #[test]
+    fn test_search_crates() {
+        let _m = mock("GET", "/?q=serde&per_page=10")
+            .with_body(
+                r#"{"crates": [{"name": "serde"}, {"name": "serde_json"}], "meta": {"total": 1234}}"#,
+            )
+            .create();
+        let results = search_crates(&client(), "serde").unwrap();
+        let names: Vec<_> = results.crates.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["serde", "serde_json"]);
+        assert_eq!(results.summary(), "Showing 2 of 1234 matches");
+        _m.assert();
+    }
+
+    #[test]
+    fn test_search_exact_match() {
+        let _m = mock("GET", "/?q=serde&per_page=10")
+            .with_body(
+                r#"{"crates": [{"name": "serde_json"}, {"name": "serde"}], "meta": {"total": 2}}"#,
+            )
+            .create();
+        let _fuzzy = mock("GET", "/?q=serd&per_page=10")
+            .with_body(r#"{"crates": [{"name": "serde"}], "meta": {"total": 1}}"#)
+            .create();
+        let results = search_crates(&client(), "serde").unwrap();
+        assert_eq!(results.exact_match("serde").unwrap().name, "serde");
+        let results = search_crates(&client(), "serd").unwrap();
+        assert_eq!(
+            results.exact_match("serd").unwrap_err().to_string(),
+            "No crate in the search results is named exactly 'serd'"
+        );
+        _m.assert();
+        _fuzzy.assert();
+    }
+
+    #[test]
+    fn test_check_link() {
+        let _moved = mock("HEAD", "/check/moved")
+            .with_status(301)
+            .with_header("location", "/check/new")
+            .expect(2)
+            .create();
+        let _new = mock("HEAD", "/check/new").expect(1).create();
+        let _gone = mock("HEAD", "/check/gone").with_status(404).create();
+        let url = format!("{}/check/moved", mockito::server_url());
+        assert_eq!(check_link(&client(), &url).unwrap(), 200);
+        let no_redirects = build_client(&ClientOptions {
+            no_redirects: true,
+            ..ClientOptions::default()
+        })
+        .unwrap();
+        assert_eq!(check_link(&no_redirects, &url).unwrap(), 301);
+        let url = format!("{}/check/gone", mockito::server_url());
+        assert_eq!(check_link(&client(), &url).unwrap(), 404);
+        _moved.assert();
+        _new.assert();
+        _gone.assert();
+    }
+
+    #[test]
+    fn test_open_pr_count() {
+        let _m = mock("GET", "/repos/a/a/pulls?state=open&per_page=1")
+            .match_header("authorization", "Bearer secret")
+            .with_header("x-total-count", "42")
+            .with_body("[]")
+            .create();
+        let token = GithubToken("secret".to_owned());
+        let count = open_pr_count(&client(), "https://github.com/a/a", &token).unwrap();
+        assert_eq!(count, 42);
+        assert_eq!(format!("{:?}", token), "<redacted>");
+        assert!(open_pr_count(&client(), "https://gitlab.com/a/a", &token).is_err());
+        _m.assert();
+    }
+
+    fn owner(login: &str, kind: &str) -> Owner {
+        Owner {
+            login: login.to_owned(),
+            kind: Some(kind.to_owned()),
+        }
+    }
+
+    #[test]
+    fn test_get_author_crates() {
+        let _user = mock("GET", "/celeo")
+            .with_body(r#"{"user":{"id":1234,"login":"Celeo","name":"Celeo"}}"#)
+            .create();
+        let _first = mock("GET", "/?page=1&per_page=100&user_id=1234")
+            .with_body(
+                r#"{"crates":[
+                    {"name":"cargo-nav","newest_version":"0.3.0","description":"Navigate to crate links"},
+                    {"name":"tool","max_version":"1.0.0-rc.1","description":"A tool\nfor things"}
+                ],"meta":{"total":3,"next_page":"?page=2&per_page=100&user_id=1234"}}"#,
+            )
+            .create();
+        let _second = mock("GET", "/?page=2&per_page=100&user_id=1234")
+            .with_body(r#"{"crates":[{"name":"x"}],"meta":{"total":3,"next_page":null}}"#)
+            .create();
+        let crates = get_author_crates(&client(), "celeo").unwrap();
+        let names = crates.iter().map(|c| c.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["cargo-nav", "tool", "x"]);
+        assert_eq!(
+            crates_table(&crates, None),
+            "1. cargo-nav  0.3.0       Navigate to crate links\n\
+             2. tool       1.0.0-rc.1  A tool for things\n\
+             3. x          -"
+        );
+        assert_eq!(
+            crates_table(&crates, Some(30)),
+            "1. cargo-nav  0.3.0       Nav…\n\
+             2. tool       1.0.0-rc.1  A t…\n\
+             3. x          -"
+        );
+        _first.assert();
+        _second.assert();
+    }
+
+    #[test]
+    fn test_collect_dependencies() {
+        let _m = mock("GET", "/deptree/1.2.0/dependencies")
+            .with_body(
+                r#"{"dependencies":[
+                    {"crate_id":"deptree-a","kind":"normal","req":"^1"},
+                    {"crate_id":"cc","kind":"build","req":"^1"},
+                    {"crate_id":"tempfile","kind":"dev","req":"^3"},
+                    {"crate_id":"deptree-b","kind":"normal","req":"^0.4"},
+                    {"crate_id":"deptree-a","kind":"normal","req":"^1","target":"cfg(unix)"},
+                    {"kind":"normal"}
+                ]}"#,
+            )
+            .create();
+        let _a = mock("GET", "/deptree-a")
+            .with_body(r#"{"crate":{"name":"deptree-a"}}"#)
+            .expect(2)
+            .create();
+        let _b = mock("GET", "/deptree-b")
+            .with_body(r#"{"crate":{"name":"deptree-b"}}"#)
+            .expect(1)
+            .create();
+        let info = CrateInfo {
+            name: "deptree".to_owned(),
+            newest_version: Some("1.2.0".to_owned()),
+            ..CrateInfo::default()
+        };
+        let names = |found: Vec<CrateInfo>| found.into_iter().map(|i| i.name).collect::<Vec<_>>();
+        assert_eq!(
+            names(collect_dependencies(&client(), &info, 1, 10, 2).unwrap()),
+            vec!["deptree-a", "deptree-b"]
+        );
+        assert_eq!(
+            names(collect_dependencies(&client(), &info, 1, 1, 2).unwrap()),
+            vec!["deptree-a"]
+        );
+        assert!(collect_dependencies(&client(), &info, 0, 10, 2)
+            .unwrap()
+            .is_empty());
+        _a.assert();
+        _b.assert();
+    }
+
+    #[test]
+    fn test_collect_dependencies_levels() {
+        let dependencies = |names: &[&str]| {
+            let listed = names
+                .iter()
+                .map(|n| format!(r#"{{"crate_id":"{}","kind":"normal"}}"#, n))
+                .collect::<Vec<_>>();
+            format!(r#"{{"dependencies":[{}]}}"#, listed.join(","))
+        };
+        let info = |name: &str| {
+            format!(
+                r#"{{"crate":{{"name":"{}","newest_version":"1.0.0"}}}}"#,
+                name
+            )
+        };
+        let mocks = vec![
+            mock("GET", "/levels/1.0.0/dependencies")
+                .with_body(dependencies(&["levels-a", "levels-b"]))
+                .expect(1)
+                .create(),
+            mock("GET", "/levels-a")
+                .with_body(info("levels-a"))
+                .expect(1)
+                .create(),
+            mock("GET", "/levels-b")
+                .with_body(info("levels-b"))
+                .expect(1)
+                .create(),
+            mock("GET", "/levels-a/1.0.0/dependencies")
+                .with_body(dependencies(&["levels-b", "levels-c"]))
+                .expect(1)
+                .create(),
+            mock("GET", "/levels-b/1.0.0/dependencies")
+                .with_body(dependencies(&["levels", "levels-c"]))
+                .expect(1)
+                .create(),
+            mock("GET", "/levels-c")
+                .with_body(info("levels-c"))
+                .expect(1)
+                .create(),
+        ];
+        let root = CrateInfo {
+            name: "levels".to_owned(),
+            newest_version: Some("1.0.0".to_owned()),
+            ..CrateInfo::default()
+        };
+        let found = collect_dependencies(&client(), &root, 2, 10, 4).unwrap();
+        let names = found.iter().map(|i| i.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["levels-a", "levels-b", "levels-c"]);
+        for m in &mocks {
+            m.assert();
+        }
+    }
+
+    #[test]
+    fn test_versions() {
+        let _m = mock("GET", "/a/versions")
+            .with_body(
+                r#"{"versions":[
+                    {"num":"1.1.0","created_at":"2024-02-01T10:00:00.000000+00:00","yanked":false},
+                    {"num":"1.0.1","created_at":"2024-01-15T10:00:00.000000+00:00","yanked":true},
+                    {"num":"1.0.0","created_at":"2024-01-01T10:00:00.000000+00:00","yanked":false}
+                ],"meta":{"total":3}}"#,
+            )
+            .create();
+        let versions = get_versions(&client(), "a").unwrap();
+        assert_eq!(versions.len(), 3);
+        assert_eq!(
+            versions_listing(&versions, 2),
+            "1.1.0  2024-02-01\n1.0.1  2024-01-15  (yanked)"
+        );
+        let url =
+            determine_link(&crate_info(), &Destination::Versions, &Config::default()).unwrap();
+        assert_eq!(url, "https://crates.io/crates/a/versions");
+        _m.assert();
+    }
+
+    #[test]
+    fn test_check_documented() {
+        let _ok = mock("GET", "/crate/a/latest/status.json")
+            .with_body(r#"{"doc_status":true,"version":"1.0.0"}"#)
+            .create();
+        let _failed = mock("GET", "/crate/a/0.1.0/status.json")
+            .with_body(r#"{"doc_status":false,"version":"0.1.0"}"#)
+            .create();
+        assert!(check_documented(&client(), "a", None).is_ok());
+        let err = check_documented(&client(), "a", Some("0.1.0")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "docs.rs failed to build the docs for a 0.1.0"
+        );
+        assert!(check_documented(&client(), "missing", None).is_err());
+        _ok.assert();
+        _failed.assert();
+    }
+
+    #[test]
+    fn test_team_url() {
+        assert_eq!(
+            team_url("https://crates.io", "github:rust-lang:libs").unwrap(),
+            "https://crates.io/teams/github:rust-lang:libs"
+        );
+        assert!(team_url("https://crates.io", "github:rust-lang").is_err());
+        assert!(team_url("https://crates.io", "github::libs").is_err());
+        assert!(team_url("https://crates.io", "alice").is_err());
+    }
+
+    #[test]
+    fn test_team_link_single() {
+        let mut info = crate_info();
+        info.owners = vec![
+            owner("alice", "user"),
+            owner("github:acme:maintainers", "team"),
+        ];
+        let url = determine_link(&info, &Destination::Team, &Config::default()).unwrap();
+        assert_eq!(url, "https://crates.io/teams/github:acme:maintainers");
+    }
+
+    #[test]
+    fn test_team_link_multiple() {
+        let mut info = crate_info();
+        info.owners = vec![
+            owner("github:acme:core", "team"),
+            owner("github:acme:release", "team"),
+        ];
+        let err = team_link(
+            &info,
+            &Config::default(),
+            &mut Prompt::new(Cursor::new(""), false, false),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Several teams own a: github:acme:core, github:acme:release"
+        );
+        let url = team_link(
+            &info,
+            &Config::default(),
+            &mut Prompt::new(Cursor::new("2\n"), true, false),
+        )
+        .unwrap();
+        assert_eq!(url, "https://crates.io/teams/github:acme:release");
+        assert!(team_link(
+            &info,
+            &Config::default(),
+            &mut Prompt::new(Cursor::new("3\n"), true, false)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_team_link_users_only() {
+        let mut info = crate_info();
+        info.owners = vec![owner("alice", "user"), owner("bob", "user")];
+        let err = determine_link(&info, &Destination::Team, &Config::default()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No teams own a; its owners are: alice, bob"
+        );
+    }
+
+    #[test]
+    fn test_owner_profile() {
+        let _crate = mock("GET", "/a")
+            .with_body(r#"{"crate":{"name":"a"}}"#)
+            .create();
+        let _owners = mock("GET", "/a/owners")
+            .with_body(
+                r#"{"users":[{"login":"github:acme:core","kind":"team"},{"login":"alice","kind":"user"}]}"#,
+            )
+            .create();
+        let trace = explain("a", Destination::OwnerProfile);
+        assert!(trace.contains(". owners: github:acme:core, alice\n"));
+        assert!(trace.ends_with("final URL: https://crates.io/users/alice"));
+        _crate.assert();
+        _owners.assert();
+    }
+
+    #[test]
+    fn test_owner_profile_missing() {
+        let mut info = crate_info();
+        info.owners = vec![owner("github:acme:core", "team")];
+        let result = determine_link(&info, &Destination::OwnerProfile, &Config::default());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_get_owners() {
+        let _m = mock("GET", "/a/owners")
+            .with_body(
+                r#"{"users":[{"id":1,"login":"alice","kind":"user"},{"id":2,"login":"github:acme:core","kind":"team"}]}"#,
+            )
+            .create();
+        let owners = get_owners(&client(), "a").unwrap();
+        assert_eq!(owners.len(), 2);
+        assert!(!owners[0].is_team());
+        assert!(owners[1].is_team());
+        _m.assert();
+    }
+
+    #[test]
+    fn test_determine_link_forum_override() {
+        let config = Config::from_toml(
+            r#"
+[crate_overrides.a]
+forum_url = "https://users.rust-lang.org/t/a"
+"#,
+        )
+        .unwrap();
+        let url = determine_link(&crate_info(), &Destination::Forum, &config).unwrap();
+        assert_eq!(url, "https://users.rust-lang.org/t/a");
+    }
+
+    #[test]
+    fn test_determine_link_forum_homepage() {
+        let mut info = crate_info();
+        info.homepage = Some("https://www.reddit.com/r/rust/comments/a".to_owned());
+        let url = determine_link(&info, &Destination::F, &Config::default()).unwrap();
+        assert_eq!(url, "https://www.reddit.com/r/rust/comments/a");
+    }
+
+    #[test]
+    fn test_determine_link_forum_missing() {
+        let result = determine_link(&crate_info(), &Destination::Forum, &Config::default());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_explain_resolution() {
+        let _m = mock("GET", "/a")
+            .with_body(r#"{"crate":{"name":"a","documentation":"c"}}"#)
+            .create();
+        assert_eq!(
+            explain("a", Destination::D),
+            format!(
+                "1. crate name: a
+2. lookup name: a
+3. destination: documentation (given)
+4. project patch: none
+5. local directory: none
+6. API URL: {}/a
+7. source: crates.io
+8. candidate links: homepage = (not set), documentation = c, repository = (not set)
+9. final URL: c",
+                mockito::server_url()
+            )
+        );
+        _m.assert();
+    }
+
+    #[test]
+    fn test_explain_resolution_fallbacks() {
+        let _m = mock("GET", "/a")
+            .with_body(r#"{"crate":{"name":"a","homepage":"https://example.com"}}"#)
+            .create();
+        assert_eq!(
+            explain("a", Destination::Forum),
+            format!(
+                "1. crate name: a
+2. lookup name: a
+3. destination: forum (given)
+4. project patch: none
+5. local directory: none
+6. API URL: {}/a
+7. source: crates.io
+8. candidate links: homepage = https://example.com, documentation = (not set), repository = (not set)
+9. config forum_url: not set
+10. homepage forum pattern: no match
+11. result: error: The forum link isn't set for that crate",
+                mockito::server_url()
+            )
+        );
+        _m.assert();
+    }
+
+    #[test]
+    fn test_explain_resolution_not_found() {
+        let _m = mock("GET", "/b").with_status(404).create();
+        let trace = explain("b", Destination::D);
+        assert!(trace.contains("\n7. crates.io: not found\n8. result: error: "));
+    }
+
+    #[test]
+    fn test_explain_resolution_mixed_case() {
+        let _m = mock("GET", "/tokio-util")
+            .with_body(
+                r#"{"crate":{"name":"tokio-util","documentation":"https://docs.rs/tokio-util"}}"#,
+            )
+            .create();
+        let trace = explain("Tokio-Util", Destination::D);
+        assert!(trace
+            .starts_with("1. crate name: Tokio-Util\n2. lookup name: tokio-util (lowercased)\n"));
+        assert!(trace.contains(&format!("API URL: {}/tokio-util\n", mockito::server_url())));
+        assert!(!trace.contains("canonical name"));
+        assert!(trace.ends_with("final URL: https://docs.rs/tokio-util"));
+        _m.assert();
+    }
+
+    #[test]
+    fn test_served_link() {
+        let _m = mock("GET", "/served")
+            .with_body(r#"{"crate":{"name":"served","documentation":"https://docs.rs/served"}}"#)
+            .create();
+        let resolve = |name: &str, destination: Option<&str>| {
+            served_link(&client(), &Config::default(), name, destination)
+        };
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let request = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream
+                .write_all(b"GET /crate/served?dest=d HTTP/1.1\r\n\r\n")
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        });
+        let (stream, _) = listener.accept().unwrap();
+        serve::handle_connection(stream, &resolve).unwrap();
+        let response = request.join().unwrap();
+        let body = response.split("\r\n\r\n").nth(1).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(body).unwrap(),
+            serde_json::json!({
+                "name": "served",
+                "destination": "documentation",
+                "url": "https://docs.rs/served"
+            })
+        );
+        _m.assert();
+
+        let err = served_link(&client(), &Config::default(), "served", Some("nope")).unwrap_err();
+        assert!(err.to_string().contains("nope"));
+    }
+
+    #[test]
+    fn test_updated_since() {
+        let _m = mock("GET", "/a")
+            .with_body(r#"{"crate":{"name":"a","updated_at":"2024-01-01T23:30:00.123456-02:00"}}"#)
+            .create();
+        let info = get_crate_info(&client(), "a").unwrap();
+        let date = |s: &str| s.parse::<NaiveDate>().unwrap();
+        assert!(updated_since(&info, date("2024-01-02")).unwrap());
+        assert!(!updated_since(&info, date("2024-01-03")).unwrap());
+        assert!(updated_since(&crate_info(), date("2024-01-01")).is_err());
+        _m.assert();
+    }
+
+    #[test]
+    fn test_community_link() {
+        let _m = mock("GET", "/a/a/HEAD/README.md")
+            .with_body("[![chat](https://img.shields.io/discord/1)](https://discord.gg/a)")
+            .create();
+        let mut info = crate_info();
+        assert!(community_link(&client(), &info).is_err());
+        info.repository = Some("https://github.com/a/a".to_owned());
+        assert_eq!(
+            community_link(&client(), &info).unwrap(),
+            "https://discord.gg/a"
+        );
+        _m.assert();
+    }
+
+    #[test]
+    fn test_inferred_homepage() {
+        let _m = mock("GET", "/sparse/sparse/HEAD/README.md")
+            .with_body("# sparse\n\nWebsite: https://sparse.example.com\n")
+            .create();
+        let mut info = crate_info();
+        assert!(inferred_homepage(&client(), &info).is_err());
+        info.repository = Some("https://github.com/sparse/sparse".to_owned());
+        assert_eq!(
+            inferred_homepage(&client(), &info).unwrap(),
+            "https://sparse.example.com"
+        );
+        _m.assert();
+    }
+
+    #[test]
+    fn test_missing_link_listing() {
+        let err = determine_link(&crate_info(), &Destination::R, &Config::default()).unwrap_err();
+        assert_eq!(
+            missing_link_listing(&crate_info(), &Config::default(), &err),
+            "The repository link isn't set for that crate\nAvailable links: Homepage: b, Documentation: c"
+        );
+        let info = CrateInfo {
+            name: "a".to_owned(),
+            ..CrateInfo::default()
+        };
+        let err = determine_link(&info, &Destination::H, &Config::default()).unwrap_err();
+        assert!(missing_link_listing(&info, &Config::default(), &err)
+            .ends_with("no links found for crate 'a'; check https://crates.io/crates/a"));
+    }
+
+    #[test]
+    fn test_limit_per_host() {
+        let urls = [
+            "https://github.com/a/a",
+            "https://docs.rs/a",
+            "https://github.com/b/b",
+            "https://github.com/c/c",
+            "file:///tmp/doc/index.html",
+            "file:///tmp/doc/other.html",
+            "https://docs.rs/b",
+        ];
+        let (open, skipped) = limit_per_host(&urls, 2);
+        assert_eq!(
+            open,
+            vec![
+                "https://github.com/a/a",
+                "https://docs.rs/a",
+                "https://github.com/b/b",
+                "file:///tmp/doc/index.html",
+                "file:///tmp/doc/other.html",
+                "https://docs.rs/b",
+            ]
+        );
+        assert_eq!(skipped, vec!["https://github.com/c/c"]);
+        let (open, skipped) = limit_per_host(&urls, usize::MAX);
+        assert_eq!(open.len(), urls.len());
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_open_link() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let link = SessionRecord::new("serde", "documentation", "https://docs.rs/serde");
+        let opener = Opener::new(Some(String::from("true")), true);
+        let mut config = Config::default();
+
+        config.pre_open_cmd = Some(String::from("false"));
+        open_link(&link, &config, &opener, Some(&path), false).unwrap();
+        assert!(!path.exists());
+
+        config.pre_open_cmd = Some(String::from("true"));
+        open_link(&link, &config, &opener, Some(&path), false).unwrap();
+        assert_eq!(session::read(&path).unwrap(), vec![link]);
+
+        let failing = Opener::new(Some(String::from("false")), true);
+        let link = SessionRecord::new("serde", "documentation", "https://docs.rs/serde");
+        assert!(open_link(&link, &config, &failing, None, false).is_err());
+        config.pre_open_cmd = Some(String::from("cargo-nav-no-such-hook"));
+        assert!(open_link(&link, &config, &opener, None, false).is_err());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_replay_session_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let replayed = dir.path().join("replayed.jsonl");
+        let recorded = dir.path().join("recorded.jsonl");
+        let links = [
+            ("serde", "https://github.com/serde-rs/serde"),
+            ("serde_json", "https://github.com/serde-rs/json"),
+            ("serde", "https://github.com/serde-rs/serde"),
+            ("rand", "https://docs.rs/rand"),
+        ];
+        for (name, url) in &links {
+            session::append(&replayed, &SessionRecord::new(name, "repository", url)).unwrap();
+        }
+        let opener = Opener::new(Some(String::from("true")), true);
+        replay_session(
+            &replayed,
+            Some(1),
+            &Config::default(),
+            &opener,
+            Some(&recorded),
+            false,
+        )
+        .unwrap();
+        let urls = session::read(&recorded)
+            .unwrap()
+            .into_iter()
+            .map(|r| (r.crate_name, r.url))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            urls,
+            vec![
+                ("serde".to_owned(), links[0].1.to_owned()),
+                ("rand".to_owned(), links[3].1.to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_all_links() {
+        let links = all_links(&crate_info(), &Config::default());
+        assert_eq!(links[0], ("crate", "https://crates.io/crates/a".to_owned()));
+        assert_eq!(links[1], ("homepage", "b".to_owned()));
+        assert_eq!(links[2], ("documentation", "c".to_owned()));
+        assert!(links
+            .iter()
+            .all(|(label, _)| *label != "repository" && *label != "homepage or docs"));
+        assert!(links.contains(&("advisory", "https://rustsec.org/packages/a.html".to_owned())));
+        let mut labels = links.iter().map(|(label, _)| *label).collect::<Vec<_>>();
+        labels.dedup();
+        assert_eq!(labels.len(), links.len());
+    }
+
+    #[test]
+    fn test_markdown_link() {
+        assert_eq!(
+            markdown_link("serde", "https://docs.rs/serde"),
+            "[serde](https://docs.rs/serde)"
+        );
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_normalize_args_cargo() {
+        assert_eq!(
+            normalize_args(args(&["cargo-nav", "nav", "serde", "d"]), true),
+            args(&["cargo-nav", "serde", "d"])
+        );
+        assert_eq!(
+            normalize_args(
+                args(&["/home/u/.cargo/bin/cargo-nav", "nav", "serde"]),
+                true
+            ),
+            args(&["/home/u/.cargo/bin/cargo-nav", "serde"])
+        );
+    }
+
+    #[test]
+    fn test_normalize_args_direct() {
+        assert_eq!(
+            normalize_args(args(&["cargo-nav", "serde", "d"]), true),
+            args(&["cargo-nav", "serde", "d"])
+        );
+    }
+
+    #[test]
+    fn test_normalize_args_crate_named_nav() {
+        let normalized = normalize_args(args(&["cargo-nav", "nav", "nav", "r"]), true);
+        assert_eq!(normalized, args(&["cargo-nav", "nav", "r"]));
+        let opt = Options::from_iter(normalized.iter());
+        assert_eq!(opt.crate_name.as_deref(), Some("nav"));
+    }
+
+    #[test]
+    fn test_normalize_args_flags_first() {
+        let normalized = normalize_args(args(&["cargo-nav", "nav", "--debug", "serde"]), true);
+        assert_eq!(normalized, args(&["cargo-nav", "--debug", "serde"]));
+        let opt = Options::from_iter(normalized.iter());
+        assert!(opt.debug);
+        assert_eq!(opt.crate_name.as_deref(), Some("serde"));
+    }
+
+    #[test]
+    fn test_normalize_args_windows() {
+        assert_eq!(
+            normalize_args(args(&["cargo-nav.exe", "nav", "serde"]), true),
+            args(&["cargo-nav.exe", "serde"])
+        );
+    }
+
+    #[test]
+    fn test_normalize_args_missing_program_name() {
+        assert_eq!(normalize_args(Vec::new(), true), args(&["cargo-nav"]));
+        assert_eq!(
+            normalize_args(args(&["", "nav", "serde"]), true),
+            args(&["cargo-nav", "serde"])
+        );
+        assert_eq!(
+            normalize_args(args(&["", "serde"]), true),
+            args(&["cargo-nav", "serde"])
+        );
+    }
+
+    #[test]
+    fn test_normalize_args_only_first_nav() {
+        assert_eq!(
+            normalize_args(args(&["cargo-nav", "nav", "nav", "nav"]), true),
+            args(&["cargo-nav", "nav", "nav"])
+        );
+    }
+
+    #[test]
+    fn test_normalize_args_not_via_cargo() {
+        let normalized = normalize_args(args(&["cargo-nav", "nav", "r"]), false);
+        assert_eq!(normalized, args(&["cargo-nav", "nav", "r"]));
+        let opt = Options::from_iter(normalized.iter());
+        assert_eq!(opt.crate_name.as_deref(), Some("nav"));
+        assert!(matches!(opt.destination, Some(Destination::R)));
+    }
+
+    #[test]
+    fn test_normalize_args_help_and_version() {
+        for flag in &["--help", "--version"] {
+            let via_cargo = Options::clap()
+                .get_matches_from_safe(normalize_args(args(&["cargo-nav", "nav", *flag]), true))
+                .unwrap_err();
+            let direct = Options::clap()
+                .get_matches_from_safe(normalize_args(args(&["cargo-nav", *flag]), false))
+                .unwrap_err();
+            assert!(matches!(
+                via_cargo.kind,
+                ErrorKind::HelpDisplayed | ErrorKind::VersionDisplayed
+            ));
+            assert_eq!(via_cargo.kind, direct.kind);
+            assert_eq!(via_cargo.message, direct.message);
+        }
+    }
+
+    #[test]
+    fn test_normalize_args_other_binary_name() {
+        assert_eq!(
+            normalize_args(args(&["nav", "nav"]), true),
+            args(&["nav", "nav"])
+        );
+    }
+
+    #[test]
+    fn test_build_info() {
+        let s = build_info();
+        assert!(!s.is_empty());
+        assert!(s.starts_with(&format!("cargo-nav {}", env!("CARGO_PKG_VERSION"))));
+    }
+
+    const NAMES: &[&str] = &[
+        "crate",
+        "homepage",
+        "documentation",
+        "repository",
+        "security",
+        "settings",
+    ];
+
+    #[test]
+    fn test_match_name() {
+        let aliases = &[("docs", "documentation")];
+        let table: &[(&str, Result<&str, &str>)] = &[
+            ("crate", Ok("crate")),
+            ("CRATE", Ok("crate")),
+            ("doc", Ok("documentation")),
+            ("docs", Ok("documentation")),
+            ("Docs", Ok("documentation")),
+            ("repo", Ok("repository")),
+            ("home", Ok("homepage")),
+            ("sec", Ok("security")),
+            ("se", Err("Destination 'se' is ambiguous; it could be: security, settings")),
+            ("documentaton", Err("Unknown destination 'documentaton'; did you mean `documentation`?")),
+            ("reposatory", Err("Unknown destination 'reposatory'; did you mean `repository`?")),
+            ("homepages", Err("Unknown destination 'homepages'; did you mean `homepage`?")),
+            ("zzz", Err("Unknown destination 'zzz'; expected one of: crate, homepage, documentation, repository, security, settings")),
+            ("", Err("No destination given")),
+        ];
+        for (input, expected) in table {
+            let result = match_name(input, NAMES, aliases).map_err(|e| e.to_string());
+            assert_eq!(
+                result,
+                expected.map_err(String::from),
+                "matching '{}'",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("docs", "documentation"), 10);
+    }
+
+    #[test]
+    fn test_parse_destination() {
+        assert!(matches!(parse_destination("d").unwrap(), Destination::D));
+        assert!(matches!(parse_destination("R").unwrap(), Destination::R));
+        assert!(matches!(
+            parse_destination("docs").unwrap(),
+            Destination::Documentation
+        ));
+        assert!(matches!(
+            parse_destination("pull").unwrap(),
+            Destination::PullRequests
+        ));
+        assert!(matches!(
+            parse_destination("FeatureFlags").unwrap(),
+            Destination::FeatureFlags
+        ));
+        assert!(matches!(
+            parse_destination("ghcr").unwrap(),
+            Destination::ContainerRegistry
+        ));
+        assert!(parse_destination("co").is_err());
+        let opt = Options::from_iter(&["cargo-nav", "serde", "repo"]);
+        assert!(matches!(opt.destination, Some(Destination::Repository)));
+    }
+
+    #[test]
+    fn test_choose_destination_given() {
+        let dest = choose_destination(
+            Some(Destination::R),
+            None,
+            true,
+            &mut Prompt::new(Cursor::new(""), false, false),
+            &mut Trace::default(),
+        )
+        .unwrap();
+        assert!(matches!(dest, Destination::R));
+    }
+
+    #[test]
+    fn test_choose_destination_default() {
+        let dest = choose_destination(
+            None,
+            None,
+            false,
+            &mut Prompt::new(Cursor::new(""), false, false),
+            &mut Trace::default(),
+        )
+        .unwrap();
+        assert!(matches!(dest, Destination::C));
+    }
+
+    #[test]
+    fn test_choose_destination_no_default_interactive() {
+        let dest = choose_destination(
+            None,
+            None,
+            true,
+            &mut Prompt::new(Cursor::new("nope\n"), true, false),
+            &mut Trace::default(),
+        );
+        assert!(dest.is_err());
+        let dest = choose_destination(
+            None,
+            None,
+            true,
+            &mut Prompt::new(Cursor::new("docs\n"), true, false),
+            &mut Trace::default(),
+        )
+        .unwrap();
+        assert!(matches!(dest, Destination::Documentation));
+        let dest = choose_destination(
+            None,
+            None,
+            true,
+            &mut Prompt::new(Cursor::new("d\n"), true, false),
+            &mut Trace::default(),
+        )
+        .unwrap();
+        assert!(matches!(dest, Destination::D));
+    }
+
+    #[test]
+    fn test_choose_destination_configured() {
+        let config = Config::from_toml("[crate_defaults]\ntokio = \"d\"\nclap = \"repo\"").unwrap();
+        let configured = |name| {
+            config
+                .crate_default(name)
+                .map(|d| parse_destination(d).unwrap())
+        };
+        let dest = choose_destination(
+            None,
+            configured("tokio"),
+            false,
+            &mut Prompt::new(Cursor::new(""), false, false),
+            &mut Trace::default(),
+        )
+        .unwrap();
+        assert!(matches!(dest, Destination::D));
+        let dest = choose_destination(
+            None,
+            configured("clap"),
+            true,
+            &mut Prompt::new(Cursor::new(""), false, false),
+            &mut Trace::default(),
+        )
+        .unwrap();
+        assert!(matches!(dest, Destination::Repository));
+        let dest = choose_destination(
+            Some(Destination::H),
+            configured("tokio"),
+            false,
+            &mut Prompt::new(Cursor::new(""), false, false),
+            &mut Trace::default(),
+        )
+        .unwrap();
+        assert!(matches!(dest, Destination::H));
+        let dest = choose_destination(
+            None,
+            configured("serde"),
+            false,
+            &mut Prompt::new(Cursor::new(""), false, false),
+            &mut Trace::default(),
+        )
+        .unwrap();
+        assert!(matches!(dest, Destination::C));
+    }
+
+    #[test]
+    fn test_choose_destination_assume_yes() {
+        let dest = choose_destination(
+            None,
+            None,
+            true,
+            &mut Prompt::new(Cursor::new(""), false, true),
+            &mut Trace::default(),
+        )
+        .unwrap();
+        assert!(matches!(dest, Destination::Crate));
+        let dest = choose_destination(
+            None,
+            None,
+            true,
+            &mut Prompt::new(Cursor::new("r\n"), true, true),
+            &mut Trace::default(),
+        )
+        .unwrap();
+        assert!(matches!(dest, Destination::Crate));
+        let opt = Options::from_iter(&["cargo-nav", "-y", "serde"]);
+        assert!(opt.assume_yes);
+    }
+
+    #[test]
+    fn test_team_link_assume_yes() {
+        let mut info = crate_info();
+        info.owners = vec![
+            owner("github:acme:core", "team"),
+            owner("github:acme:release", "team"),
+        ];
+        let url = team_link(
+            &info,
+            &Config::default(),
+            &mut Prompt::new(Cursor::new(""), false, true),
+        )
+        .unwrap();
+        assert_eq!(url, "https://crates.io/teams/github:acme:core");
+    }
+
+    #[test]
+    fn test_choose_destination_no_default_not_interactive() {
+        let result = choose_destination(
+            None,
+            None,
+            true,
+            &mut Prompt::new(Cursor::new("d\n"), false, false),
+            &mut Trace::default(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_get_crate_info_just_name() {
+        let _m = mock("GET", "/a")
+            .with_body(r#"{"crate":{"name":"a"}}"#)
+            .create();
+        let info = get_crate_info(&client(), "a").unwrap();
+        assert_eq!(info.name, "a");
+        assert_eq!(info.homepage, None);
+        assert_eq!(info.documentation, None);
+        assert_eq!(info.repository, None);
+        _m.assert();
+    }
+
+    #[test]
+    fn test_split_crate_version() {
+        assert_eq!(
+            split_crate_version("serde", None).unwrap(),
+            ("serde".to_owned(), None)
+        );
+        assert_eq!(
+            split_crate_version("serde@1.0.0", None).unwrap(),
+            ("serde".to_owned(), Some("1.0.0".to_owned()))
+        );
+        assert_eq!(
+            split_crate_version("serde_json@1.0.0-rc.1", None).unwrap(),
+            ("serde_json".to_owned(), Some("1.0.0-rc.1".to_owned()))
+        );
+        assert_eq!(
+            split_crate_version("serde", Some("1.0.0".to_owned())).unwrap(),
+            ("serde".to_owned(), Some("1.0.0".to_owned()))
+        );
+        assert_eq!(
+            split_crate_version("serde@1.0.0", Some("1.0.0".to_owned())).unwrap(),
+            ("serde".to_owned(), Some("1.0.0".to_owned()))
+        );
+        for invalid in &["@1.0.0", "serde@", "serde@latest", "ser de@1", "a@1@2"] {
+            assert!(
+                split_crate_version(invalid, None).is_err(),
+                "'{}' should be invalid",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_split_crate_version_conflict() {
+        let err = split_crate_version("serde@1.0.0", Some("1.0.1".to_owned())).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The crate is given as serde@1.0.0 but --crate-version is 1.0.1"
+        );
+    }
+
+    #[test]
+    fn test_check_strict_version() {
+        for version in &["1.0.0", "0.1.12", "1.0.0-rc.1", "2.3.4+build.5", "10.20.30"] {
+            assert!(check_strict_version(version).is_ok(), "{}", version);
+        }
+        for version in &[
+            "1", "1.0", "v1.0.0", "1.0.0.0", "01.0.0", "latest", "^1.2.3", "",
+        ] {
+            assert!(check_strict_version(version).is_err(), "{}", version);
+        }
+        assert!(check_strict_version("1.0")
+            .unwrap_err()
+            .to_string()
+            .starts_with("'1.0' isn't a full semver version: "));
+    }
+
+    #[test]
+    fn test_json_field() {
+        let info = CrateInfo {
+            name: "a".to_owned(),
+            homepage: Some("https://a.example.com".to_owned()),
+            documentation: Some("https://docs.rs/a".to_owned()),
+            repository: Some("https://github.com/acme/a".to_owned()),
+            updated_at: Some("2023-03-01T12:00:00.000000+00:00".to_owned()),
+            downloads: Some(1234),
+            recent_downloads: Some(56),
+            ..CrateInfo::default()
+        };
+        let config = Config::default();
+        let expected = [
+            ("name", "a"),
+            ("homepage", "https://a.example.com"),
+            ("documentation", "https://docs.rs/a"),
+            ("repository", "https://github.com/acme/a"),
+            ("updated_at", "2023-03-01T12:00:00.000000+00:00"),
+            ("downloads", "1234"),
+            ("recent_downloads", "56"),
+            ("crate", "https://crates.io/crates/a"),
+        ];
+        assert_eq!(expected.len(), JSON_FIELDS.len());
+        for (field, value) in expected.iter() {
+            assert_eq!(
+                json_field(&info, field, &config).as_deref(),
+                Some(*value),
+                "field {}",
+                field
+            );
+        }
+        let bare = CrateInfo {
+            name: "a".to_owned(),
+            ..CrateInfo::default()
+        };
+        for field in &[
+            "homepage",
+            "documentation",
+            "repository",
+            "updated_at",
+            "downloads",
+        ] {
+            assert_eq!(json_field(&bare, field, &config), None);
+        }
+        assert!(
+            Options::from_iter_safe(&["cargo-nav", "a", "--print-json-field", "nope"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_crate_info_links() {
+        assert_eq!(
+            crate_info().links(),
+            vec![("homepage", "b"), ("documentation", "c")]
+        );
+        assert!(CrateInfo::default().links().is_empty());
+    }
+
+    #[test]
+    fn test_audit_links_args() {
+        let opt =
+            Options::from_iter(&["cargo-nav", "audit-links", "serde", "--output", "out.json"]);
+        match opt.command {
+            Some(Command::AuditLinks { crate_name, output }) => {
+                assert_eq!(crate_name, "serde");
+                assert_eq!(output.as_deref(), Some(Path::new("out.json")));
+            }
+            other => panic!("unexpected command {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_config_args() {
+        let opt = Options::from_iter(&["cargo-nav", "config", "set", "crate_defaults.tokio", "d"]);
+        match opt.command {
+            Some(Command::Config(ConfigCommand::Set { key, value })) => {
+                assert_eq!(key, "crate_defaults.tokio");
+                assert_eq!(value, "d");
+            }
+            other => panic!("unexpected command {:?}", other),
+        }
+        let opt = Options::from_iter(&["cargo-nav", "config", "reset"]);
+        assert!(matches!(
+            opt.command,
+            Some(Command::Config(ConfigCommand::Reset))
+        ));
+    }
+
+    #[test]
+    fn test_stats_command() {
+        let opt = Options::from_iter(&["cargo-nav", "stats"]);
+        assert!(matches!(opt.command, Some(Command::Stats { limit: 10 })));
+        let opt = Options::from_iter(&["cargo-nav", "stats", "--limit", "3"]);
+        assert!(matches!(opt.command, Some(Command::Stats { limit: 3 })));
+        let opt = Options::from_iter(&["cargo-nav", "--record-stats", "serde", "d"]);
+        assert!(opt.record_stats);
+        assert_eq!(opt.crate_name.as_deref(), Some("serde"));
+    }
+
+    #[test]
+    fn test_archived_version_link() {
+        let _m = mock("GET", "/archived")
+            .with_body(
+                r#"{"crate":{"name":"archived","max_version":"2.0.0-rc.1"},"versions":[{"num":"2.0.0-rc.1"},{"num":"1.4.0"}]}"#,
+            )
+            .create();
+        let info = get_crate_info(&client(), "archived").unwrap();
+        let config = Config::default();
+        assert_eq!(
+            archived_version_link(&info, Some("1.4.0"), &config).unwrap(),
+            "https://crates.io/crates/archived/1.4.0"
+        );
+        assert_eq!(
+            archived_version_link(&info, None, &config).unwrap(),
+            "https://crates.io/crates/archived/2.0.0-rc.1"
+        );
+        assert_eq!(
+            determine_link(&info, &Destination::ArchivedVersion, &config).unwrap(),
+            "https://crates.io/crates/archived/2.0.0-rc.1"
+        );
+        assert_eq!(
+            archived_version_link(&info, Some("0.1.0"), &config)
+                .unwrap_err()
+                .to_string(),
+            "archived has no version 0.1.0"
+        );
+        _m.assert();
+    }
+
+    #[test]
+    fn test_determine_link_container_registry() {
+        let mut info = crate_info();
+        assert!(
+            determine_link(&info, &Destination::ContainerRegistry, &Config::default()).is_err()
+        );
+        info.repository = Some("https://github.com/acme/a".to_owned());
+        let url =
+            determine_link(&info, &Destination::ContainerRegistry, &Config::default()).unwrap();
+        assert_eq!(url, "https://github.com/acme/a/pkgs/container/a");
+        info.repository = Some("https://gitlab.com/acme/a".to_owned());
+        let err =
+            determine_link(&info, &Destination::ContainerRegistry, &Config::default()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "https://gitlab.com/acme/a isn't a GitHub repository"
+        );
+    }
+
+    #[test]
+    fn test_determine_link_homepage_or_docs() {
+        let mut info = crate_info();
+        info.homepage = Some("https://example.com".to_owned());
+        info.documentation = Some("https://example.com/docs".to_owned());
+        let destination = parse_destination("homepageordocs").unwrap();
+        let url = determine_link(&info, &destination, &Config::default()).unwrap();
+        assert_eq!(url, "https://example.com");
+        info.homepage = None;
+        let url = determine_link(&info, &destination, &Config::default()).unwrap();
+        assert_eq!(url, "https://example.com/docs");
+        info.documentation = None;
+        let url = determine_link(&info, &destination, &Config::default()).unwrap();
+        assert_eq!(url, "https://docs.rs/a");
+    }
+
+    #[test]
+    fn test_first_available() {
+        let mut trace = Trace::default();
+        let link = first_available(
+            vec![
+                ("one", None),
+                ("two", Some("b".to_owned())),
+                ("three", Some("c".to_owned())),
+            ],
+            &mut trace,
+        );
+        assert_eq!(link.as_deref(), Some("b"));
+        assert_eq!(trace.to_string(), "1. one: not set\n2. two: b");
+        assert_eq!(first_available(vec![("one", None)], &mut trace), None);
+    }
+
+    #[test]
+    fn test_links_encode_hostile_input() {
+        let config = Config::default();
+        for text in urls::HOSTILE {
+            let mut info = crate_info();
+            info.name = text.to_string();
+            info.max_version = Some(text.to_string());
+            info.owners = vec![Owner {
+                login: text.to_string(),
+                kind: None,
+            }];
+            let mut links = [
+                Destination::Crate,
+                Destination::FeatureFlags,
+                Destination::Settings,
+                Destination::Advisory,
+                Destination::Sourcegraph,
+                Destination::Versions,
+                Destination::RawIndex,
+                Destination::ReproducibleBuilds,
+                Destination::OwnerProfile,
+                Destination::ArchivedVersion,
+                Destination::Archive,
+            ]
+            .iter()
+            .map(|d| determine_link(&info, d, &config).unwrap())
+            .collect::<Vec<_>>();
+            links.push(features_link(&info, &[], Some(text)));
+            if !text.is_empty() {
+                let login = format!("github:{}:t", text);
+                links.push(team_url("https://crates.io", &login).unwrap());
+            }
+            for link in &links {
+                let url = reqwest::Url::parse(link).unwrap();
+                assert_eq!(url.query(), None, "{}", link);
+                assert_eq!(url.fragment(), None, "{}", link);
+                assert!(
+                    url.path_segments()
+                        .unwrap()
+                        .any(|s| urls::decode(s).contains(text)),
+                    "{} in {}",
+                    text,
+                    link
+                );
+            }
+
+            let url = reqwest::Url::parse(&similar_crates_link(&info, &config)).unwrap();
+            assert_eq!(url.path(), "/search");
+            assert_eq!(url.query_pairs().next().unwrap().1, *text);
+            info.categories = vec![text.to_string()];
+            let url = reqwest::Url::parse(&similar_crates_link(&info, &config)).unwrap();
+            assert_eq!(url.query(), None);
+            assert_eq!(url.path_segments().unwrap().count(), 2);
+        }
+    }
+
+    #[test]
+    fn test_with_docs_theme() {
+        assert_eq!(
+            with_docs_theme("https://docs.rs/serde", DocsTheme::Dark),
+            "https://docs.rs/serde?theme=dark"
+        );
+        assert_eq!(
+            with_docs_theme(
+                "https://docs.rs/crate/a/latest/features#full",
+                DocsTheme::Ayu
+            ),
+            "https://docs.rs/crate/a/latest/features?theme=ayu#full"
+        );
+        assert_eq!(
+            with_docs_theme("https://serde.rs", DocsTheme::Light),
+            "https://serde.rs"
+        );
+    }
+
+    #[test]
+    fn test_theme_flag() {
+        let opt = Options::from_iter(&["cargo-nav", "--theme", "Dark", "serde", "d"]);
+        assert_eq!(opt.theme, Some(DocsTheme::Dark));
+        assert!(Options::from_iter_safe(&["cargo-nav", "--theme", "sepia", "serde"]).is_err());
+    }
+
+    #[test]
+    fn test_index_path() {
+        assert_eq!(index_path("a"), "1/a");
+        assert_eq!(index_path("cc"), "2/cc");
+        assert_eq!(index_path("syn"), "3/s/syn");
+        assert_eq!(index_path("serde"), "se/rd/serde");
+        assert_eq!(index_path("Inflector"), "in/fl/inflector");
+        assert_eq!(index_path("rand"), "ra/nd/rand");
+    }
+
+    #[test]
+    fn test_determine_link_raw_index() {
+        let mut info = crate_info();
+        info.name = "serde_json".to_owned();
+        let destination = parse_destination("index").unwrap();
+        assert!(matches!(destination, Destination::RawIndex));
+        let url = determine_link(&info, &destination, &Config::default()).unwrap();
+        assert_eq!(url, "https://index.crates.io/se/rd/serde_json");
+    }
+
+    #[test]
+    fn test_determine_link_sourcegraph() {
+        let mut info = crate_info();
+        let url = determine_link(&info, &Destination::Sourcegraph, &Config::default()).unwrap();
+        assert_eq!(url, "https://sourcegraph.com/crates/a");
+        info.repository = Some("https://github.com/acme/a.git".to_owned());
+        let url = determine_link(&info, &Destination::Sourcegraph, &Config::default()).unwrap();
+        assert_eq!(url, "https://sourcegraph.com/github.com/acme/a");
+        info.repository = Some("https://git.example.com/acme/a".to_owned());
+        let url = determine_link(&info, &Destination::Sourcegraph, &Config::default()).unwrap();
+        assert_eq!(url, "https://sourcegraph.com/crates/a");
+    }
+
+    #[test]
+    fn test_determine_link_discussions() {
+        let mut info = crate_info();
+        assert!(determine_link(&info, &Destination::Discussions, &Config::default()).is_err());
+        info.repository = Some("https://github.com/acme/a".to_owned());
+        let url = determine_link(&info, &Destination::Discussions, &Config::default()).unwrap();
+        assert_eq!(url, "https://github.com/acme/a/discussions");
+        info.repository = Some("https://gitlab.com/acme/a".to_owned());
+        let url = determine_link(&info, &Destination::Discussions, &Config::default()).unwrap();
+        assert_eq!(
+            url,
+            "https://gitlab.com/acme/a/-/issues?scope=all&sort=created_date"
+        );
+    }
+
+    #[test]
+    fn test_archive_link() {
+        let info = CrateInfo {
+            name: "serde".to_owned(),
+            max_version: Some("1.0.152".to_owned()),
+            ..CrateInfo::default()
+        };
+        let base = mockito::server_url();
+        assert_eq!(
+            archive_link(&info, None).unwrap(),
+            format!("{}/serde/serde-1.0.152.crate", base)
+        );
+        assert_eq!(
+            archive_link(&info, Some("1.0.0")).unwrap(),
+            format!("{}/serde/serde-1.0.0.crate", base)
+        );
+        assert_eq!(
+            determine_link(&info, &Destination::Archive, &Config::default()).unwrap(),
+            format!("{}/serde/serde-1.0.152.crate", base)
+        );
+        assert!(archive_link(&crate_info(), None).is_err());
+    }
+
+    #[test]
+    fn test_download_archive() {
+        let _m = mock("GET", "/packed/packed-0.1.0.crate")
+            .with_body("archive bytes")
+            .create();
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("{}/packed/packed-0.1.0.crate", mockito::server_url());
+        let path = download_archive(&client(), &url, dir.path()).unwrap();
+        assert_eq!(path, dir.path().join("packed-0.1.0.crate"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "archive bytes");
+        _m.assert();
+    }
+
+    #[test]
+    fn test_download_archive_missing() {
+        let _m = mock("GET", "/packed/packed-9.9.9.crate")
+            .with_status(404)
+            .create();
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("{}/packed/packed-9.9.9.crate", mockito::server_url());
+        assert!(download_archive(&client(), &url, dir.path()).is_err());
+        assert!(!dir.path().join("packed-9.9.9.crate").exists());
+        _m.assert();
+    }
+
+    #[test]
+    fn test_archived_version_link_no_version() {
+        let result = archived_version_link(&crate_info(), None, &Config::default());
+        assert!(result.is_err());
+        let url = archived_version_link(&crate_info(), Some("0.2.0"), &Config::default()).unwrap();
+        assert_eq!(url, "https://crates.io/crates/a/0.2.0");
+    }
+
+    #[test]
+    fn test_similar_crates_categories() {
+        let _m = mock("GET", "/clap")
+            .with_body(r#"{"crate":{"name":"clap","categories":["command-line-interface","parser-implementations"]}}"#)
+            .create();
+        let info = get_crate_info(&client(), "clap").unwrap();
+        assert_eq!(
+            info.categories,
+            vec!["command-line-interface", "parser-implementations"]
+        );
+        let url = determine_link(&info, &Destination::SimilarCrates, &Config::default()).unwrap();
+        assert_eq!(url, "https://crates.io/categories/command-line-interface");
+        _m.assert();
+    }
+
+    #[test]
+    fn test_get_crate_info_keywords() {
+        let _m = mock("GET", "/old-json")
+            .with_body(r#"{"crate":{"name":"old-json","description":"Parses JSON","keywords":["json","deprecated"]}}"#)
+            .create();
+        let info = get_crate_info(&client(), "old-json").unwrap();
+        assert_eq!(info.keywords, vec!["json", "deprecated"]);
+        _m.assert();
+    }
+
+    #[test]
+    fn test_similar_crates_no_categories() {
+        let _m = mock("GET", "/uncategorized")
+            .with_body(r#"{"crate":{"name":"uncategorized"}}"#)
+            .create();
+        let info = get_crate_info(&client(), "uncategorized").unwrap();
+        assert!(info.categories.is_empty());
+        let url = determine_link(&info, &Destination::SimilarCrates, &Config::default()).unwrap();
+        assert_eq!(url, "https://crates.io/search?q=uncategorized");
+        _m.assert();
+    }
+
+    #[test]
+    fn test_get_crate_info_all() {
+        let _m = mock("GET", "/a")
+            .with_body(
+                r#"{"crate":{"name":"a","homepage":"b","documentation":"c","repository":"d","other":"info"}}"#,
+            )
+            .create();
+        let info = get_crate_info(&client(), "a").unwrap();
+        assert_eq!(info.name, "a");
+        assert_eq!(info.homepage, Some("b".to_owned()));
+        assert_eq!(info.documentation, Some("c".to_owned()));
+        assert_eq!(info.repository, Some("d".to_owned()));
+        _m.assert();
+    }
+
+    #[test]
+    fn test_get_crate_info_renamed() {
+        let _m = mock("GET", "/Serde-Json")
+            .with_body(r#"{"crate":{"name":"serde_json","repository":"https://github.com/serde-rs/json"}}"#)
+            .create();
+        let info = get_crate_info(&client(), "Serde-Json").unwrap();
+        assert_eq!(info.name, "serde_json");
+        assert_eq!(
+            canonical_name_notice("Serde-Json", &info).unwrap(),
+            r#""Serde-Json" is published as "serde_json"; using that name"#
+        );
+        assert!(canonical_name_notice("serde_json", &info).is_none());
+        let url = determine_link(&info, &Destination::Crate, &Config::default()).unwrap();
+        assert_eq!(url, "https://crates.io/crates/serde_json");
+        _m.assert();
+    }
+
+    #[test]
+    fn test_lookup_name_mixed_case() {
+        assert_eq!(lookup_name("serde", false), "serde");
+        assert_eq!(lookup_name("Tokio-Util", false), "tokio-util");
+        let _m = mock("GET", "/tokio-util")
+            .with_body(r#"{"crate":{"name":"tokio-util"}}"#)
+            .create();
+        let info = get_crate_info(&client(), &lookup_name("Tokio-Util", false)).unwrap();
+        assert_eq!(info.name, "tokio-util");
+        let url = determine_link(&info, &Destination::Crate, &Config::default()).unwrap();
+        assert_eq!(url, "https://crates.io/crates/tokio-util");
+        _m.assert();
+    }
+
+    #[test]
+    fn test_lookup_name_exact() {
+        assert_eq!(lookup_name("Tokio-Util", true), "Tokio-Util");
+        // a crate differing only by case isn't assumed to be the lowercase one
+        let _m = mock("GET", "/Casey")
+            .with_body(r#"{"crate":{"name":"Casey"}}"#)
+            .create();
+        let info = get_crate_info(&client(), &lookup_name("Casey", true)).unwrap();
+        assert_eq!(info.name, "Casey");
+        assert!(canonical_name_notice("Casey", &info).is_none());
+        let url = determine_link(&info, &Destination::Crate, &Config::default()).unwrap();
+        assert_eq!(url, "https://crates.io/crates/Casey");
+        _m.assert();
+    }
+
+    #[test]
+    fn test_log_levels_default() {
+        let levels = log_levels(None, &Config::default()).unwrap();
+        assert_eq!(
+            levels,
+            LogLevels {
+                level: LevelFilter::Info,
+                targets: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_log_levels_precedence() {
+        let level = |flag, env: Option<&'static str>| {
+            let config = Config::from_sources(Some(r#"log_level = "error""#), |name| {
+                env.filter(|_| name == "CARGO_NAV_LOG").map(str::to_owned)
+            })
+            .unwrap();
+            log_levels(flag, &config).unwrap().level
+        };
+        assert_eq!(level(None, None), LevelFilter::Error);
+        assert_eq!(level(None, Some("warn")), LevelFilter::Warn);
+        assert_eq!(
+            level(Some(LevelFilter::Trace), Some("warn")),
+            LevelFilter::Trace
+        );
+        let config = Config::from_toml(r#"log_level = "loud""#).unwrap();
+        assert!(log_levels(None, &config).is_err());
+    }
+
+    #[test]
+    fn test_log_levels_targets() {
+        let levels = log_levels(Some(LevelFilter::Debug), &Config::default()).unwrap();
+        assert_eq!(
+            levels.targets,
+            vec![("hyper::proto".to_owned(), LevelFilter::Info)]
+        );
+        let config = Config::from_toml(
+            r#"
+[log_targets]
+"hyper::proto" = "debug"
+reqwest = "warn"
+"#,
+        )
+        .unwrap();
+        let levels = log_levels(Some(LevelFilter::Debug), &config).unwrap();
+        assert_eq!(
+            levels.targets,
+            vec![
+                ("hyper::proto".to_owned(), LevelFilter::Debug),
+                ("reqwest".to_owned(), LevelFilter::Warn),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_color_flag() {
+        let opt = Options::from_iter(&["cargo-nav", "serde"]);
+        assert_eq!(opt.color, ColorChoice::Auto);
+        let opt = Options::from_iter(&["cargo-nav", "--color", "never", "serde"]);
+        assert_eq!(opt.color, ColorChoice::Never);
+        assert!(Options::from_iter_safe(&["cargo-nav", "--color", "yes", "serde"]).is_err());
+    }
+
+    #[test]
+    fn test_log_level_flag() {
+        let opt = Options::from_iter(&["cargo-nav", "--log-level", "TRACE", "serde"]);
+        assert_eq!(opt.log_level, Some(LevelFilter::Trace));
+        let result = Options::from_iter_safe(&["cargo-nav", "-d", "--log-level", "warn", "serde"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_recent_versions() {
+        let _m = mock("GET", "/checked/versions")
+            .with_body(r#"{"versions":[{"num":"0.2.0"},{"num":"0.1.0"}]}"#)
+            .create();
+        let listing = recent_versions(&client(), "checked", 1, false).unwrap();
+        assert_eq!(listing.as_deref(), Some("0.2.0  unknown date"));
+        _m.assert();
+    }
+
+    #[test]
+    fn test_recent_versions_no_version_check() {
+        let _m = mock("GET", "/unchecked/versions")
+            .with_body(r#"{"versions":[{"num":"0.1.0"}]}"#)
+            .expect(0)
+            .create();
+        let listing = recent_versions(&client(), "unchecked", 10, true).unwrap();
+        assert_eq!(listing, None);
+        _m.assert();
+    }
+
+    #[test]
+    fn test_version_change() {
+        let _first = mock("GET", "/watched-before")
+            .with_body(r#"{"crate":{"name":"watched","newest_version":"1.0.0"}}"#)
+            .create();
+        let first = get_crate_info(&client(), "watched-before").unwrap();
+        let _second = mock("GET", "/watched-after")
+            .with_body(r#"{"crate":{"name":"watched","newest_version":"1.1.0"}}"#)
+            .create();
+        let second = get_crate_info(&client(), "watched-after").unwrap();
+        assert_eq!(
+            version_change(None, &first).as_deref(),
+            Some("watched 1.0.0 is the newest version")
+        );
+        assert_eq!(version_change(Some("1.0.0"), &first), None);
+        assert_eq!(
+            version_change(Some("1.0.0"), &second).as_deref(),
+            Some("watched has a new version: 1.1.0 (was 1.0.0)")
+        );
+    }
+
+    #[test]
+    fn test_newest_version_fallback() {
+        let _m = mock("GET", "/unlabeled")
+            .with_body(
+                r#"{"crate":{"name":"unlabeled"},"versions":[{"num":"0.3.0"},{"num":"0.2.0"}]}"#,
+            )
+            .create();
+        let info = get_crate_info(&client(), "unlabeled").unwrap();
+        assert_eq!(newest_version(&info), Some("0.3.0"));
+        assert_eq!(newest_version(&CrateInfo::default()), None);
+        assert_eq!(version_change(None, &CrateInfo::default()), None);
+    }
+
+    #[test]
+    fn test_get_raw_response() {
+        let _m = mock("GET", "/raw")
+            .with_body(r#"{"crate":{"homepage":null,"name":"raw","unexpected":[1,2]}}"#)
+            .create();
+        let body = get_raw_response(&client(), "raw").unwrap();
+        assert_eq!(
+            body,
+            r#"{
+  "crate": {
+    "homepage": null,
+    "name": "raw",
+    "unexpected": [
+      1,
+      2
+    ]
+  }
+}"#
+        );
+        _m.assert();
+    }
+
+    #[test]
+    fn test_get_raw_response_not_json() {
+        let _m = mock("GET", "/raw-html")
+            .with_body("<html>maintenance</html>")
+            .create();
+        let body = get_raw_response(&client(), "raw-html").unwrap();
+        assert_eq!(body, "<html>maintenance</html>");
+        _m.assert();
     }
 
     #[test]
-    fn test_get_crate_info_all() {
+    fn test_get_crate_info_not_found() {
+        let result = get_crate_info(&client(), "b");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_batch_links() {
+        let mocks = ["batch-c", "batch-a", "batch-b"]
+            .iter()
+            .map(|name| {
+                mock("GET", format!("/{}", name).as_str())
+                    .with_body(format!(
+                        r#"{{"crate":{{"name":"{0}","repository":"https://example.com/{0}"}}}}"#,
+                        name
+                    ))
+                    .expect(2)
+                    .create()
+            })
+            .collect::<Vec<_>>();
+        let names = ["batch-c", "batch-missing", "batch-a", "batch-b"]
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>();
+        let links = batch_links(
+            &client(),
+            &names,
+            &Destination::R,
+            &Config::default(),
+            None,
+            3,
+        );
+        let listed = links.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>();
+        assert_eq!(listed, names);
+        assert_eq!(links[0].1.as_ref().unwrap(), "https://example.com/batch-c");
+        assert!(links[1].1.is_err());
+        assert_eq!(links[2].1.as_ref().unwrap(), "https://example.com/batch-a");
+        assert_eq!(links[3].1.as_ref().unwrap(), "https://example.com/batch-b");
+        let sorted = batch_links(
+            &client(),
+            &names,
+            &Destination::R,
+            &Config::default(),
+            Some(CrateOrder::Name),
+            3,
+        );
+        let listed = sorted.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>();
+        assert_eq!(listed, ["batch-a", "batch-b", "batch-c", "batch-missing"]);
+        for m in mocks {
+            m.assert();
+        }
+    }
+
+    #[test]
+    fn test_sort_crates() {
+        let listed = |name: &str, downloads: Option<u64>, updated_at: Option<&str>| {
+            let info = CrateInfo {
+                name: name.to_owned(),
+                downloads,
+                updated_at: updated_at.map(str::to_owned),
+                ..crate_info()
+            };
+            (name.to_owned(), Ok(info))
+        };
+        let mut crates = vec![
+            listed("b", Some(10), Some("2021-03-01T00:00:00.000000+00:00")),
+            ("missing".to_owned(), Err(anyhow!("not found"))),
+            listed("c", None, None),
+            listed("a", Some(500), Some("2020-01-01T00:00:00.000000+00:00")),
+            listed("d", Some(20), Some("2022-06-15T00:00:00.000000+00:00")),
+        ];
+        let names = |crates: &[(String, Result<CrateInfo>)]| {
+            crates
+                .iter()
+                .map(|(n, _)| n.as_str())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        sort_crates(&mut crates, CrateOrder::Name);
+        assert_eq!(names(&crates), "a,b,c,d,missing");
+        sort_crates(&mut crates, CrateOrder::Downloads);
+        assert_eq!(names(&crates), "a,d,b,c,missing");
+        sort_crates(&mut crates, CrateOrder::Updated);
+        assert_eq!(names(&crates), "d,b,a,c,missing");
+    }
+
+    #[test]
+    fn test_get_crate_info_404() {
+        let _m = mock("GET", "/missing").with_status(404).create();
+        let err = get_crate_info(&client(), "missing").unwrap_err();
+        assert!(is_not_found(&err));
+        _m.assert();
+    }
+
+    #[test]
+    fn test_git_dependency_info() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            r#"
+[dependencies]
+internal = { git = "https://github.com/acme/internal", branch = "next" }
+"#,
+        )
+        .unwrap();
+        let info = git_dependency_info("internal", dir.path()).unwrap();
+        assert_eq!(info.name, "internal");
+        assert_eq!(
+            info.repository.as_deref(),
+            Some("https://github.com/acme/internal/tree/next")
+        );
+        assert_eq!(info.documentation, None);
+        assert!(git_dependency_info("other", dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_path_dependency_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = dir.path().join("app");
+        let local = dir.path().join("local");
+        fs::create_dir(&app).unwrap();
+        fs::create_dir(&local).unwrap();
+        fs::write(
+            app.join("Cargo.toml"),
+            r#"
+[dependencies]
+local = { path = "../local" }
+serde = "1"
+"#,
+        )
+        .unwrap();
+        let found = path_dependency_dir("local", &app).unwrap().unwrap();
+        assert_eq!(found, local.canonicalize().unwrap());
+        assert!(path_dependency_dir("serde", &app).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_local_docs_path() {
+        assert_eq!(
+            local_docs_path(Path::new("/project/target"), "serde-json"),
+            Path::new("/project/target/doc/serde_json/index.html")
+        );
+    }
+
+    #[test]
+    fn test_local_docs_link() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("target");
+        fs::create_dir_all(target.join("doc").join("my_crate")).unwrap();
+        fs::write(target.join("doc/my_crate/index.html"), "").unwrap();
+        let url = local_docs_link("my-crate", &[dir.path().join("other"), target]).unwrap();
+        assert!(url.starts_with("file://"));
+        assert!(url.ends_with("/target/doc/my_crate/index.html"));
+    }
+
+    #[test]
+    fn test_local_docs_link_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = local_docs_link("my-crate", &[dir.path().join("target")]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"No local docs found for "my-crate"; build them with: cargo doc -p my-crate"#
+        );
+    }
+
+    #[test]
+    fn test_lazy_client_no_redirects() {
+        let timings = Timings::new(false, Instant::now());
+        let client = LazyClient::new(ClientOptions::default(), &timings);
+        let _m = mock("HEAD", "/lazy-org/name")
+            .with_status(301)
+            .with_header("location", "/lazy-moved/name")
+            .create();
+        let repository = format!("{}/lazy-org/name", mockito::server_url());
+        let moved = moved_repository(client.no_redirects(), &repository).unwrap();
+        assert_eq!(
+            moved,
+            Some(format!("{}/lazy-moved/name", mockito::server_url()))
+        );
+        assert!(client.client.get().is_none());
+        _m.assert();
+    }
+
+    #[test]
+    fn test_checks_renames() {
+        let checks = |args: &[&str], destination: Destination| {
+            let opt = Options::from_iter([&["cargo-nav"], args, &["serde"]].concat());
+            checks_renames(&opt, &destination)
+        };
+        assert!(!checks(&[], Destination::Security));
+        assert!(!checks(&["--print"], Destination::Security));
+        assert!(!checks(&["--json"], Destination::Security));
+        assert!(checks(&["--check"], Destination::Security));
+        assert!(!checks(&["--check"], Destination::D));
+        assert!(!checks(
+            &["--check", "--no-follow-renames"],
+            Destination::Security
+        ));
+        assert!(checks(&["--community"], Destination::C));
+    }
+
+    #[test]
+    fn test_moved_repository() {
+        let client = build_client(&ClientOptions {
+            no_redirects: true,
+            ..ClientOptions::default()
+        })
+        .unwrap();
+        let _m1 = mock("HEAD", "/old-org/name")
+            .with_status(301)
+            .with_header("location", "/new-org/name")
+            .create();
+        let moved = moved_repository(
+            &client,
+            &format!("{}/old-org/name.git", mockito::server_url()),
+        )
+        .unwrap();
+        assert_eq!(
+            moved,
+            Some(format!("{}/new-org/name", mockito::server_url()))
+        );
+        _m1.assert();
+
+        let _m2 = mock("HEAD", "/org/same")
+            .with_status(301)
+            .with_header("location", "/org/same/")
+            .create();
+        let _m3 = mock("HEAD", "/org/temporary")
+            .with_status(302)
+            .with_header("location", "/other/temporary")
+            .create();
+        let _m4 = mock("HEAD", "/org/stays").with_status(200).create();
+        for path in &["/org/same", "/org/temporary", "/org/stays"] {
+            let repository = format!("{}{}", mockito::server_url(), path);
+            assert_eq!(moved_repository(&client, &repository).unwrap(), None);
+        }
+
+        let _m5 = mock("HEAD", "/org/nowhere").with_status(308).create();
+        let repository = format!("{}/org/nowhere", mockito::server_url());
+        assert!(moved_repository(&client, &repository).is_err());
+    }
+
+    #[test]
+    fn test_uses_repository() {
+        assert!(Destination::Security.uses_repository());
+        assert!(Destination::R.uses_repository());
+        assert!(!Destination::D.uses_repository());
+        assert!(!Destination::Crate.uses_repository());
+    }
+
+    #[test]
+    fn test_build_client_ignore_tls_errors() {
+        let client = build_client(&ClientOptions {
+            ignore_tls_errors: true,
+            ..ClientOptions::default()
+        });
+        assert!(client.is_ok());
+    }
+
+    #[test]
+    fn test_client_options_min_tls_version() {
+        let opt = Options::from_iter(&["cargo-nav", "--min-tls-version", "1.2", "serde"]);
+        let options = ClientOptions::from_options(&opt, &Environment::new(true));
+        assert_eq!(options.min_tls_version, Some(tls::Version::TLS_1_2));
+        assert!(build_client(&options).is_ok());
+
+        let opt = Options::from_iter(&["cargo-nav", "serde"]);
+        assert_eq!(
+            ClientOptions::from_options(&opt, &Environment::new(true)).min_tls_version,
+            None
+        );
+    }
+
+    #[test]
+    fn test_client_options_http2_prior_knowledge() {
+        let opt = Options::from_iter(&["cargo-nav", "--http2-prior-knowledge", "serde"]);
+        let options = ClientOptions::from_options(&opt, &Environment::new(true));
+        assert!(options.http2_prior_knowledge);
+        assert!(build_client(&options).is_ok());
+
+        let opt = Options::from_iter(&["cargo-nav", "serde"]);
+        assert!(!ClientOptions::from_options(&opt, &Environment::new(true)).http2_prior_knowledge);
+    }
+
+    #[test]
+    fn test_no_env() {
+        env::set_var(environment::CARGO_TARGET_DIR, "/elsewhere");
+        let dir = tempfile::tempdir().unwrap();
+        let with_env = target_dirs(dir.path(), &Environment::new(true));
+        assert_eq!(with_env[0], Path::new("/elsewhere"));
+        let without_env = target_dirs(dir.path(), &Environment::new(false));
+        assert!(without_env.iter().all(|d| d != Path::new("/elsewhere")));
+
+        let opt = Options::from_iter(&["cargo-nav", "serde"]);
+        assert!(ClientOptions::from_options(&opt, &Environment::new(false)).no_proxy);
+        assert!(!ClientOptions::from_options(&opt, &Environment::new(true)).no_proxy);
+    }
+
+    #[test]
+    fn test_parse_header() {
+        let header = parse_header("X-Mirror-Region:  eu ").unwrap();
+        assert_eq!(header.name, "x-mirror-region");
+        assert_eq!(header.value, "eu");
+        assert_eq!(format!("{:?}", header), r#"x-mirror-region: "eu""#);
+        assert!(parse_header("no separator").is_err());
+        assert!(parse_header("bad name: value").is_err());
+        assert!(parse_header(": value").is_err());
+    }
+
+    #[test]
+    fn test_parse_header_sensitive() {
+        let header = parse_header("Authorization: Bearer hunter2").unwrap();
+        assert!(header.value.is_sensitive());
+        assert_eq!(format!("{:?}", header), "authorization: <redacted>");
+        let header = parse_header("X-Api-Key: hunter2").unwrap();
+        assert!(!format!("{:?}", header).contains("hunter2"));
+    }
+
+    #[test]
+    fn test_custom_headers_sent() {
         let _m = mock("GET", "/a")
+            .match_header("x-first", "1")
+            .match_header("x-second", "two")
+            .with_body(r#"{"crate":{"name":"a"}}"#)
+            .create();
+        let client = build_client(&ClientOptions {
+            headers: vec![
+                parse_header("X-First: 1").unwrap(),
+                parse_header("X-Second: two").unwrap(),
+            ],
+            ..ClientOptions::default()
+        })
+        .unwrap();
+        let info = get_crate_info(&client, "a").unwrap();
+        assert_eq!(info.name, "a");
+        _m.assert();
+    }
+
+    #[test]
+    fn test_parse_tls_version() {
+        assert_eq!(parse_tls_version("1.3").unwrap(), tls::Version::TLS_1_3);
+        assert!(parse_tls_version("1.4").is_err());
+        assert!(parse_tls_version("tls1.2").is_err());
+    }
+
+    #[test]
+    fn test_project_patch_git() {
+        let dir = tempfile::tempdir().unwrap();
+        let member = dir.path().join("member");
+        fs::create_dir(&member).unwrap();
+        fs::write(
+            member.join("Cargo.toml"),
+            "[dependencies]\nserde = \"1.0.150\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            r#"
+[workspace]
+members = ["member"]
+
+[patch.crates-io]
+serde = { git = "https://github.com/acme/serde", rev = "abc" }
+"#,
+        )
+        .unwrap();
+        let patch = project_patch("serde", &member).unwrap().unwrap();
+        assert_eq!(patch.requested_version.as_deref(), Some("1.0.150"));
+        assert!(patch.local_dir().is_none());
+        assert_eq!(
+            patch.notice("serde"),
+            format!(
+                r#"Note: "serde" (requested version 1.0.150) is overridden by [patch.crates-io] in {}; using https://github.com/acme/serde/tree/abc"#,
+                dir.path().join("Cargo.toml").display()
+            )
+        );
+        assert!(project_patch("log", &member).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_project_patch_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = dir.path().join("app");
+        let forked = dir.path().join("rand");
+        fs::create_dir(&app).unwrap();
+        fs::create_dir(&forked).unwrap();
+        fs::write(
+            app.join("Cargo.toml"),
+            r#"
+[dependencies]
+rand = "0.8"
+
+[patch.crates-io]
+rand = { path = "../rand" }
+"#,
+        )
+        .unwrap();
+        let patch = project_patch("rand", &app).unwrap().unwrap();
+        assert_eq!(patch.local_dir(), Some(forked.canonicalize().unwrap()));
+    }
+
+    #[test]
+    fn test_json_output_found() {
+        let output = JsonOutput::found(&crate_info(), None, &Ok("c".to_owned()));
+        assert_eq!(
+            serde_json::to_string(&output).unwrap(),
+            r#"{"name":"a","found":true,"homepage":"b","documentation":"c","url":"c"}"#
+        );
+    }
+
+    #[test]
+    fn test_json_output_found_without_link() {
+        let link = determine_link(&crate_info(), &Destination::R, &Config::default());
+        let output = JsonOutput::found(&crate_info(), None, &link);
+        assert_eq!(
+            serde_json::to_string(&output).unwrap(),
+            r#"{"name":"a","found":true,"error":"The repository link isn't set for that crate","homepage":"b","documentation":"c"}"#
+        );
+    }
+
+    #[test]
+    fn test_json_text_array() {
+        let output = JsonOutput::found(&crate_info(), None, &Ok("c".to_owned()));
+        let object = json_text(&output, false).unwrap();
+        assert!(object.starts_with('{'));
+        assert_eq!(json_text(&output, true).unwrap(), format!("[{}]", object));
+    }
+
+    #[test]
+    fn test_json_output_not_found() {
+        let _m = mock("GET", "/foo").with_status(404).create();
+        let err = get_crate_info(&client(), "foo").unwrap_err();
+        let output = JsonOutput::not_found("foo", &err);
+        assert_eq!(
+            serde_json::to_string(&output).unwrap(),
+            r#"{"name":"foo","found":false,"error":"not found"}"#
+        );
+        _m.assert();
+    }
+
+    const FEATURES_BODY: &str = r#"{
+        "crate": {"name": "a"},
+        "versions": [
+            {"num": "2.0.0", "yanked": true, "features": {}},
+            {"num": "1.1.0", "features": {
+                "default": ["std"],
+                "std": [],
+                "derive": ["dep:a_derive"],
+                "full": ["derive", "rand", "std", "rand/std"]
+            }}
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_api_response_strict_first() {
+        let body = r#"{"crate":{"name":"a","homepage":"b"},"versions":[{"num":"1.0.0"}]}"#;
+        let data = parse_api_response(body, "u", |_| None::<CrateInfoWrapper>).unwrap();
+        assert_eq!(data.crate_info.homepage.as_deref(), Some("b"));
+        assert_eq!(data.versions.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_api_response_lenient() {
+        let body = r#"{
+            "crate": {"id": "a", "homepage": 7, "documentation": "c", "downloads": "many"},
+            "versions": [{"num": 1}, {"num": "1.0.0", "yanked": "no", "crate_size": 10}]
+        }"#;
+        let data = parse_api_response(body, "u", lenient_crate_info).unwrap();
+        assert_eq!(data.crate_info.name, "a");
+        assert_eq!(data.crate_info.homepage, None);
+        assert_eq!(data.crate_info.documentation.as_deref(), Some("c"));
+        assert_eq!(data.crate_info.downloads, None);
+        assert_eq!(data.versions.len(), 1);
+        assert_eq!(data.versions[0].num, "1.0.0");
+        assert!(!data.versions[0].yanked);
+        assert_eq!(data.versions[0].crate_size, Some(10));
+    }
+
+    #[test]
+    fn test_parse_api_response_unexpected_shape() {
+        for body in &[r#"{"krate":{"name":"a"}}"#, "[]", "not json"] {
+            let err = parse_api_response(
+                body,
+                "https://crates.io/api/v1/crates/a",
+                lenient_crate_info,
+            )
+            .unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "The crates.io API returned data in an unexpected shape; please update \
+                 cargo-nav or file an issue (requested https://crates.io/api/v1/crates/a)"
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_owners_lenient() {
+        let _m = mock("GET", "/a/owners")
+            .with_body(r#"{"users":[{"id":1,"login":"alice","kind":3},{"id":2}]}"#)
+            .create();
+        let owners = get_owners(&client(), "a").unwrap();
+        assert_eq!(owners.len(), 1);
+        assert_eq!(owners[0].login, "alice");
+        assert_eq!(owners[0].kind, None);
+        _m.assert();
+    }
+
+    #[test]
+    fn test_get_crate_info_versions() {
+        let _m = mock("GET", "/a").with_body(FEATURES_BODY).create();
+        let info = get_crate_info(&client(), "a").unwrap();
+        assert_eq!(info.versions.len(), 2);
+        let latest = info.version(None).unwrap();
+        assert_eq!(latest.num, "1.1.0");
+        assert!(info.version(Some("2.0.0")).unwrap().yanked);
+        assert!(info.version(Some("0.1.0")).is_none());
+        _m.assert();
+    }
+
+    #[test]
+    fn test_features() {
+        let _m = mock("GET", "/a").with_body(FEATURES_BODY).create();
+        let info = get_crate_info(&client(), "a").unwrap();
+        let version = info.version(None).unwrap();
+        let requested = vec!["full".to_owned(), "nope".to_owned()];
+        assert_eq!(version.unknown_features(&requested), vec!["nope"]);
+        assert_eq!(
+            version.optional_dependencies("full"),
+            vec!["a_derive", "rand"]
+        );
+        assert!(version.optional_dependencies("std").is_empty());
+        assert_eq!(
+            features_link(&info, &requested, None),
+            "https://docs.rs/crate/a/latest/features#full"
+        );
+        assert_eq!(
+            features_link(&info, &["nope".to_owned()], Some("1.1.0")),
+            "https://docs.rs/crate/a/1.1.0/features"
+        );
+        _m.assert();
+    }
+
+    const SIZE_BODY: &str = r#"{
+        "crate": {"name": "a", "documentation": "c"},
+        "versions": [
+            {"num": "1.0.0", "crate_size": 1536, "created_at": "2023-05-04T10:20:30.123456+00:00"},
+            {"num": "0.0.1", "crate_size": null}
+        ]
+    }"#;
+
+    #[test]
+    fn test_latest_version_yanked() {
+        let _m = mock("GET", "/yanked-latest")
             .with_body(
-                r#"{"crate":{"name":"a","homepage":"b","documentation":"c","repository":"d","other":"info"}}"#,
+                r#"{
+                    "crate": {"name": "yanked-latest", "documentation": "c"},
+                    "versions": [
+                        {"num": "1.1.0", "yanked": true},
+                        {"num": "1.0.0", "yanked": false}
+                    ]
+                }"#,
             )
             .create();
-        let info = get_crate_info("a").unwrap();
-        assert_eq!(info.name, "a");
-        assert_eq!(info.homepage, Some("b".to_owned()));
-        assert_eq!(info.documentation, Some("c".to_owned()));
-        assert_eq!(info.repository, Some("d".to_owned()));
+        let info = get_crate_info(&client(), "yanked-latest").unwrap();
+        assert_eq!(
+            info.describe(&Config::default()),
+            "Documentation: c (latest version yanked)"
+        );
+        assert_eq!(
+            info_report(&info, &Config::default(), None, None),
+            "Documentation: c (latest version yanked)\nVersion: 1.0.0\nSize: unknown"
+        );
         _m.assert();
     }
 
     #[test]
-    fn test_get_crate_info_not_found() {
-        let result = get_crate_info("b");
-        assert!(result.is_err());
+    fn test_truncate_to_width() {
+        let text = "A generic serialization/deserialization\n  framework";
+        assert_eq!(
+            truncate_to_width(text, 80),
+            "A generic serialization/deserialization framework"
+        );
+        assert_eq!(truncate_to_width(text, 20), "A generic serializa…");
+        assert_eq!(truncate_to_width(text, 24), "A generic serialization…");
+        assert_eq!(truncate_to_width(text, 24).chars().count(), 24);
+        assert_eq!(truncate_to_width("déjà vu", 5), "déjà…");
+        assert_eq!(truncate_to_width("abc", 0), "…");
+    }
+
+    #[test]
+    fn test_info_report_description() {
+        let info = CrateInfo {
+            name: "a".to_owned(),
+            documentation: Some("c".to_owned()),
+            description: Some("A long description\nof the crate".to_owned()),
+            ..CrateInfo::default()
+        };
+        assert_eq!(
+            info_report(&info, &Config::default(), None, None),
+            "Documentation: c\nDescription: A long description of the crate\nNo version information found"
+        );
+        assert_eq!(
+            info_report(&info, &Config::default(), None, Some(24)),
+            "Documentation: c\nDescription: A long des…\nNo version information found"
+        );
+    }
+
+    #[test]
+    fn test_crate_size() {
+        let _m = mock("GET", "/a").with_body(SIZE_BODY).create();
+        let info = get_crate_info(&client(), "a").unwrap();
+        assert_eq!(info.version(None).unwrap().crate_size, Some(1536));
+        assert_eq!(info.version(Some("0.0.1")).unwrap().crate_size, None);
+        assert_eq!(
+            info_report(&info, &Config::default(), None, None),
+            "Documentation: c\nVersion: 1.0.0\nPublished: 2023-05-04\nSize: 1.5 KiB"
+        );
+        assert_eq!(
+            info_report(&info, &Config::default(), Some("0.0.1"), None),
+            "Documentation: c\nVersion: 0.0.1\nSize: unknown"
+        );
+        let output = JsonOutput::found(&info, None, &Ok("c".to_owned()));
+        assert_eq!(output.crate_size, Some(1536));
+        let output = JsonOutput::found(&info, Some("0.0.1"), &Ok("c".to_owned()));
+        assert!(!serde_json::to_string(&output)
+            .unwrap()
+            .contains("crate_size"));
+        _m.assert();
+    }
+
+    #[test]
+    fn test_days_since_epoch() {
+        assert_eq!(days_since_epoch("1970-01-01"), Some(0));
+        assert_eq!(days_since_epoch("2000-03-01"), Some(11_017));
+        assert_eq!(days_since_epoch("2024-01-01T00:00:00Z"), Some(19_723));
+        assert_eq!(days_since_epoch("2024-13-01"), None);
+        assert_eq!(days_since_epoch("yesterday"), None);
+    }
+
+    fn bucket(date: &str, downloads: u64) -> DownloadBucket {
+        DownloadBucket {
+            date: date.to_owned(),
+            downloads,
+        }
+    }
+
+    #[test]
+    fn test_download_trend() {
+        let today = days_since_epoch("2024-03-31").unwrap();
+        let buckets = vec![
+            bucket("2024-03-31", 10),
+            bucket("2024-03-31", 5),
+            bucket("2024-03-02", 20),
+            bucket("2024-03-01", 7),
+            bucket("2024-01-02", 3),
+            bucket("2024-01-01", 1000),
+            bucket("not a date", 1000),
+        ];
+        let trend = DownloadTrend::from_buckets(&buckets, today);
+        assert_eq!(
+            trend,
+            DownloadTrend {
+                last_90_days: 45,
+                last_30_days: 35,
+                prior_30_days: 7,
+            }
+        );
+        assert_eq!(trend.arrow(), "↑");
+        let falling = DownloadTrend {
+            last_30_days: 50,
+            prior_30_days: 100,
+            ..DownloadTrend::default()
+        };
+        assert_eq!(falling.arrow(), "↓");
+        assert_eq!(falling.direction(), "down");
+        assert_eq!(DownloadTrend::default().arrow(), "→");
+        assert_eq!(DownloadTrend::default().direction(), "steady");
+    }
+
+    #[test]
+    fn test_json_output_trend() {
+        let trend = DownloadTrend {
+            last_90_days: 150,
+            last_30_days: 100,
+            prior_30_days: 50,
+        };
+        let output = JsonOutput::found(&crate_info(), None, &Ok("c".to_owned()));
+        assert_eq!(
+            serde_json::to_string(&output.with_trend(Some(&trend))).unwrap(),
+            r#"{"name":"a","found":true,"homepage":"b","documentation":"c","url":"c","last_30_days":100,"prior_30_days":50,"download_trend":"up"}"#
+        );
+        assert!(optional_download_trend(&client(), "a", true).is_none());
+    }
+
+    #[test]
+    fn test_get_download_trend() {
+        let _m = mock("GET", "/a/downloads")
+            .with_body(r#"{"version_downloads":[{"version":1,"downloads":3,"date":"2000-01-01"}],"meta":{"extra_downloads":[]}}"#)
+            .create();
+        let trend = get_download_trend(&client(), "a").unwrap();
+        assert_eq!(trend, DownloadTrend::default());
+        _m.assert();
+    }
+
+    #[test]
+    fn test_downloads_report() {
+        let mut info = crate_info();
+        assert_eq!(downloads_report(&info, None), "Downloads: unknown total");
+        info.downloads = Some(1000);
+        info.recent_downloads = Some(45);
+        assert_eq!(
+            downloads_report(&info, None),
+            "Downloads: 1000 total, 45 in the last 90 days"
+        );
+        let trend = DownloadTrend {
+            last_90_days: 45,
+            last_30_days: 35,
+            prior_30_days: 7,
+        };
+        assert_eq!(
+            downloads_report(&info, Some(&trend)),
+            "Downloads: 1000 total, 45 in the last 90 days (↑ 35 in the last 30 days, 7 in the 30 before)"
+        );
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MiB");
+    }
+
+    #[test]
+    fn test_download_size() {
+        let _m = mock("HEAD", "/a/1.0.0/download")
+            .with_header("content-length", "2048")
+            .create();
+        assert_eq!(download_size(&client(), "a", "1.0.0").unwrap(), 2048);
+        _m.assert();
     }
 
     #[test]
     fn test_get_crate_info_some() {
-        let s = format!("{}", crate_info());
+        let s = crate_info().describe(&Config::default());
         assert_eq!(s, "Homepage: b, Documentation: c");
     }
 
@@ -278,14 +7003,45 @@ mod tests {
     fn test_get_crate_info_none() {
         let info = CrateInfo {
             name: "a".to_owned(),
-            homepage: None,
-            documentation: None,
-            repository: None,
+            ..CrateInfo::default()
         };
-        let s = format!("{}", info);
+        let s = info.describe(&Config::default());
         assert_eq!(
             s,
             "no links found for crate 'a'; check https://crates.io/crates/a"
         );
+        let mut config = Config::default();
+        config.web_base = Some("https://crates.internal.example.com/".to_owned());
+        assert_eq!(
+            info.describe(&config),
+            "no links found for crate 'a'; check https://crates.internal.example.com/crates/a"
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_external_crate_info() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("resolver.sh");
+        fs::write(
+            &script,
+            "#!/bin/sh\necho '{\"name\": \"'$1'\", \"repository\": \"https://git.example.com/'$1'\"}'\n",
+        )
+        .unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        let info = external_crate_info(&script.display().to_string(), "internal-lib").unwrap();
+        assert_eq!(info.name, "internal-lib");
+        assert_eq!(
+            info.repository.as_deref(),
+            Some("https://git.example.com/internal-lib")
+        );
+        assert_eq!(info.homepage, None);
+
+        let err = external_crate_info("echo not json", "internal-lib").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Could not read the resolver's crate info"));
     }
 }
@@ -0,0 +1,346 @@
+//! Reading dependency information from the current project's `Cargo.toml`.
+
+use crate::forge;
+use anyhow::{anyhow, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use toml::Value;
+
+/// Dependency tables that can appear at the top level and under `target.<cfg>`.
+const DEPENDENCY_TABLES: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Where a patched crate's source actually comes from.
+#[derive(Debug, PartialEq)]
+pub enum PatchSource {
+    Git(GitDependency),
+    Path(String),
+}
+
+/// A `[patch.crates-io]` or `[replace]` entry overriding a crates.io crate.
+#[derive(Debug, PartialEq)]
+pub struct Patch {
+    /// Which manifest section the entry is in.
+    pub section: &'static str,
+    pub source: PatchSource,
+}
+
+/// A parsed `Cargo.toml`.
+#[derive(Debug)]
+pub struct Manifest {
+    value: Value,
+}
+
+/// Which commit of a git dependency is used.
+#[derive(Debug, PartialEq)]
+pub enum GitReference {
+    Rev(String),
+    Branch(String),
+    Tag(String),
+}
+
+/// A dependency pulled straight from a git repository.
+#[derive(Debug, PartialEq)]
+pub struct GitDependency {
+    pub url: String,
+    pub reference: Option<GitReference>,
+}
+
+impl GitDependency {
+    /// Web URL of the repository, pointing at the pinned commit if there is one.
+    pub fn web_url(&self) -> String {
+        match &self.reference {
+            Some(GitReference::Rev(r))
+            | Some(GitReference::Branch(r))
+            | Some(GitReference::Tag(r)) => forge::tree_url(&self.url, r),
+            None => forge::normalize_repo_url(&self.url),
+        }
+    }
+}
+
+impl Manifest {
+    /// Parse manifest TOML text.
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let value = text
+            .parse::<Value>()
+            .map_err(|e| anyhow!("Invalid Cargo.toml: {}", e))?;
+        Ok(Manifest { value })
+    }
+
+    /// Read and parse a manifest file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Could not read {}: {}", path.display(), e))?;
+        Manifest::from_toml(&text)
+    }
+
+    /// Find the declaration of a dependency on the crate, in any dependency table.
+    ///
+    /// Renamed dependencies are matched by their `package` name.
+    pub fn dependency(&self, crate_name: &str) -> Option<&Value> {
+        let mut tables = DEPENDENCY_TABLES
+            .iter()
+            .filter_map(|t| self.value.get(t))
+            .collect::<Vec<_>>();
+        if let Some(targets) = self.value.get("target").and_then(Value::as_table) {
+            for target in targets.values() {
+                tables.extend(DEPENDENCY_TABLES.iter().filter_map(|t| target.get(t)));
+            }
+        }
+        if let Some(workspace) = self
+            .value
+            .get("workspace")
+            .and_then(|w| w.get("dependencies"))
+        {
+            tables.push(workspace);
+        }
+        tables
+            .into_iter()
+            .filter_map(Value::as_table)
+            .flat_map(|t| t.iter())
+            .find(|(key, value)| declares(key, value, crate_name))
+            .map(|(_, value)| value)
+    }
+
+    /// Version requirement the project declares for the crate, if any.
+    pub fn requested_version(&self, crate_name: &str) -> Option<&str> {
+        let declaration = self.dependency(crate_name)?;
+        declaration
+            .as_str()
+            .or_else(|| declaration.get("version")?.as_str())
+    }
+
+    /// The `[patch.crates-io]` or `[replace]` entry for the crate, if there is one.
+    pub fn patch(&self, crate_name: &str) -> Option<Patch> {
+        let patched = self
+            .value
+            .get("patch")
+            .and_then(|p| p.get("crates-io"))
+            .and_then(Value::as_table)
+            .and_then(|t| {
+                t.iter()
+                    .find(|(key, value)| declares(key, value, crate_name))
+            });
+        if let Some((_, declaration)) = patched {
+            return patch_source(declaration).map(|source| Patch {
+                section: "[patch.crates-io]",
+                source,
+            });
+        }
+        // `[replace]` keys are package IDs like "foo:1.0.0"
+        let (_, declaration) = self
+            .value
+            .get("replace")?
+            .as_table()?
+            .iter()
+            .find(|(key, _)| key.split(':').next() == Some(crate_name))?;
+        patch_source(declaration).map(|source| Patch {
+            section: "[replace]",
+            source,
+        })
+    }
+
+    /// The crate's path, relative to the manifest, if it's declared as a path dependency.
+    pub fn path_dependency(&self, crate_name: &str) -> Option<&str> {
+        self.dependency(crate_name)?.get("path")?.as_str()
+    }
+
+    /// The crate's git dependency declaration, if it's declared as one.
+    pub fn git_dependency(&self, crate_name: &str) -> Option<GitDependency> {
+        git_source(self.dependency(crate_name)?)
+    }
+}
+
+/// Whether a dependency table entry is for the crate, accounting for renames.
+fn declares(key: &str, declaration: &Value, crate_name: &str) -> bool {
+    declaration
+        .get("package")
+        .and_then(Value::as_str)
+        .unwrap_or(key)
+        == crate_name
+}
+
+/// Read where a patch entry points.
+fn patch_source(declaration: &Value) -> Option<PatchSource> {
+    git_source(declaration).map(PatchSource::Git).or_else(|| {
+        declaration
+            .get("path")?
+            .as_str()
+            .map(|p| PatchSource::Path(p.to_owned()))
+    })
+}
+
+/// Read the git URL and reference out of a dependency declaration.
+pub fn git_source(declaration: &Value) -> Option<GitDependency> {
+    let url = declaration.get("git")?.as_str()?.to_owned();
+    let field = |name: &str| {
+        declaration
+            .get(name)
+            .and_then(Value::as_str)
+            .map(str::to_owned)
+    };
+    let reference = field("rev")
+        .map(GitReference::Rev)
+        .or_else(|| field("branch").map(GitReference::Branch))
+        .or_else(|| field("tag").map(GitReference::Tag));
+    Some(GitDependency { url, reference })
+}
+
+/// Every `Cargo.toml` from the directory upward, nearest first.
+pub fn find_manifests(start: &Path) -> Vec<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join("Cargo.toml"))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_manifests, GitDependency, GitReference, Manifest, PatchSource};
+    use std::fs;
+
+    const MANIFEST: &str = r#"
+[package]
+name = "app"
+
+[dependencies]
+serde = "1"
+internal = { git = "https://github.com/acme/internal.git", rev = "abc123" }
+renamed = { package = "tools", git = "git@gitlab.com:acme/tools.git", branch = "dev" }
+local = { path = "../local" }
+
+[target.'cfg(unix)'.dev-dependencies]
+unixy = { git = "https://github.com/acme/unixy" }
+"#;
+
+    #[test]
+    fn test_git_dependency_rev() {
+        let manifest = Manifest::from_toml(MANIFEST).unwrap();
+        let dep = manifest.git_dependency("internal").unwrap();
+        assert_eq!(
+            dep,
+            GitDependency {
+                url: "https://github.com/acme/internal.git".to_owned(),
+                reference: Some(GitReference::Rev("abc123".to_owned())),
+            }
+        );
+        assert_eq!(
+            dep.web_url(),
+            "https://github.com/acme/internal/tree/abc123"
+        );
+    }
+
+    #[test]
+    fn test_git_dependency_renamed_branch() {
+        let manifest = Manifest::from_toml(MANIFEST).unwrap();
+        assert!(manifest.git_dependency("renamed").is_none());
+        let dep = manifest.git_dependency("tools").unwrap();
+        assert_eq!(dep.web_url(), "https://gitlab.com/acme/tools/-/tree/dev");
+    }
+
+    #[test]
+    fn test_git_dependency_target_unpinned() {
+        let manifest = Manifest::from_toml(MANIFEST).unwrap();
+        let dep = manifest.git_dependency("unixy").unwrap();
+        assert_eq!(dep.reference, None);
+        assert_eq!(dep.web_url(), "https://github.com/acme/unixy");
+    }
+
+    #[test]
+    fn test_git_dependency_not_git() {
+        let manifest = Manifest::from_toml(MANIFEST).unwrap();
+        assert!(manifest.git_dependency("serde").is_none());
+        assert!(manifest.git_dependency("local").is_none());
+        assert!(manifest.git_dependency("missing").is_none());
+    }
+
+    #[test]
+    fn test_path_dependency() {
+        let manifest = Manifest::from_toml(MANIFEST).unwrap();
+        assert_eq!(manifest.path_dependency("local"), Some("../local"));
+        assert_eq!(manifest.path_dependency("internal"), None);
+        assert_eq!(manifest.path_dependency("serde"), None);
+    }
+
+    const PATCHED: &str = r#"
+[dependencies]
+serde = "1.0.150"
+rand = { version = "0.8", features = ["small_rng"] }
+log = "0.4"
+
+[patch.crates-io]
+serde = { git = "https://github.com/acme/serde", branch = "fix" }
+rand = { path = "../rand" }
+
+[replace]
+"log:0.4.17" = { git = "https://github.com/acme/log" }
+"#;
+
+    #[test]
+    fn test_requested_version() {
+        let manifest = Manifest::from_toml(PATCHED).unwrap();
+        assert_eq!(manifest.requested_version("serde"), Some("1.0.150"));
+        assert_eq!(manifest.requested_version("rand"), Some("0.8"));
+        assert_eq!(manifest.requested_version("missing"), None);
+    }
+
+    #[test]
+    fn test_patch_git() {
+        let manifest = Manifest::from_toml(PATCHED).unwrap();
+        let patch = manifest.patch("serde").unwrap();
+        assert_eq!(patch.section, "[patch.crates-io]");
+        match patch.source {
+            PatchSource::Git(dep) => {
+                assert_eq!(dep.web_url(), "https://github.com/acme/serde/tree/fix")
+            }
+            other => panic!("unexpected patch source {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_patch_path() {
+        let manifest = Manifest::from_toml(PATCHED).unwrap();
+        let patch = manifest.patch("rand").unwrap();
+        assert_eq!(patch.source, PatchSource::Path("../rand".to_owned()));
+    }
+
+    #[test]
+    fn test_replace() {
+        let manifest = Manifest::from_toml(PATCHED).unwrap();
+        let patch = manifest.patch("log").unwrap();
+        assert_eq!(patch.section, "[replace]");
+        assert!(matches!(patch.source, PatchSource::Git(_)));
+        assert!(manifest.patch("missing").is_none());
+        assert!(Manifest::from_toml(MANIFEST)
+            .unwrap()
+            .patch("serde")
+            .is_none());
+    }
+
+    #[test]
+    fn test_workspace_dependencies() {
+        let manifest = Manifest::from_toml(
+            r#"
+[workspace.dependencies]
+shared = { git = "https://github.com/acme/shared", tag = "v2" }
+"#,
+        )
+        .unwrap();
+        let dep = manifest.git_dependency("shared").unwrap();
+        assert_eq!(dep.web_url(), "https://github.com/acme/shared/tree/v2");
+    }
+
+    #[test]
+    fn test_find_manifests() {
+        let dir = tempfile::tempdir().unwrap();
+        let member = dir.path().join("member");
+        fs::create_dir(&member).unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        fs::write(member.join("Cargo.toml"), "").unwrap();
+        let found = find_manifests(&member);
+        assert_eq!(found[0], member.join("Cargo.toml"));
+        assert_eq!(found[1], dir.path().join("Cargo.toml"));
+    }
+}
@@ -0,0 +1,139 @@
+//! Opening links in the default browser, or with the command from `--opener-cmd`.
+
+use crate::hook;
+use anyhow::{anyhow, Result};
+use log::debug;
+use std::process::Command;
+
+/// How links get opened.
+pub struct Opener {
+    /// Command to open links with instead of the default browser.
+    command: Option<String>,
+    /// Whether to wait for the command to exit after opening each link.
+    wait: bool,
+}
+
+impl Opener {
+    /// Open links with `command` if there is one, or the default browser.
+    ///
+    /// Only a command can be waited for; `webbrowser` hands links off to the
+    /// browser without any way to know when it's closed.
+    pub fn new(command: Option<String>, wait: bool) -> Self {
+        Opener { command, wait }
+    }
+
+    /// Open the link.
+    pub fn open(&self, url: &str) -> Result<()> {
+        match &self.command {
+            Some(template) => run(template, url, self.wait),
+            None => webbrowser::open(url).map_err(|e| anyhow!(e)),
+        }
+    }
+}
+
+/// Split the opener command into the program and its arguments for the link.
+///
+/// `{url}` is replaced with the link, which is added at the end if the command
+/// doesn't have the placeholder.
+pub fn opener_args(template: &str, url: &str) -> Vec<String> {
+    let mut args = hook::hook_args(template, url);
+    if !template.contains(hook::URL_PLACEHOLDER) {
+        args.push(url.to_owned());
+    }
+    args
+}
+
+/// Start the opener command for the link, waiting for it to exit if `wait`.
+///
+/// Without `wait` the command is left running and never waited for here; once
+/// cargo-nav exits, which it does soon after opening, the system reaps it.
+pub fn run(template: &str, url: &str, wait: bool) -> Result<()> {
+    let args = opener_args(template, url);
+    let (program, rest) = args
+        .split_first()
+        .filter(|_| !template.trim().is_empty())
+        .ok_or_else(|| anyhow!("The opener command is empty"))?;
+    let mut child = Command::new(program)
+        .args(rest)
+        .spawn()
+        .map_err(|e| anyhow!("Could not run the opener command {}: {}", program, e))?;
+    if wait {
+        debug!("Waiting for {} to exit", program);
+        let status = child.wait()?;
+        if !status.success() {
+            return Err(anyhow!(
+                "The opener command {} exited with {}",
+                program,
+                status
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{opener_args, run};
+
+    #[test]
+    fn test_opener_args() {
+        assert_eq!(
+            opener_args("firefox --new-window", "https://docs.rs/serde"),
+            vec!["firefox", "--new-window", "https://docs.rs/serde"]
+        );
+        assert_eq!(
+            opener_args("open -a Safari {url} --background", "https://docs.rs/a"),
+            vec!["open", "-a", "Safari", "https://docs.rs/a", "--background"]
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_run_wait() {
+        use std::fs;
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("browser.sh");
+        fs::write(&script, "echo opening > \"$1\"\necho closed >> \"$1\"\n").unwrap();
+        let opened = dir.path().join("opened");
+        let template = format!("sh {} {{url}}", script.display());
+        run(&template, &opened.display().to_string(), true).unwrap();
+        assert_eq!(fs::read_to_string(&opened).unwrap(), "opening\nclosed\n");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_run_no_wait() {
+        use std::{fs, process::Command};
+        let dir = tempfile::tempdir().unwrap();
+        // the command can't finish until the test writes to the FIFO, so run
+        // only returns here if it didn't wait
+        let fifo = dir.path().join("fifo");
+        assert!(Command::new("mkfifo")
+            .arg(&fifo)
+            .status()
+            .unwrap()
+            .success());
+        let script = dir.path().join("browser.sh");
+        let body = format!("read line < {}\necho opened > \"$1\"\n", fifo.display());
+        fs::write(&script, body).unwrap();
+        let opened = dir.path().join("opened");
+        let template = format!("sh {}", script.display());
+        run(&template, &opened.display().to_string(), false).unwrap();
+        assert!(!opened.exists());
+        fs::write(&fifo, "go\n").unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_run_exit_status() {
+        assert!(run("false", "https://docs.rs/a", true).is_err());
+        assert!(run("false", "https://docs.rs/a", false).is_ok());
+        assert!(run("true", "https://docs.rs/a", true).is_ok());
+    }
+
+    #[test]
+    fn test_run_errors() {
+        assert!(run("  ", "https://docs.rs/a", false).is_err());
+        assert!(run("cargo-nav-no-such-browser", "https://docs.rs/a", true).is_err());
+    }
+}
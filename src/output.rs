@@ -0,0 +1,200 @@
+//! Writing results to stdout, tolerating readers that stop reading early.
+
+use std::{
+    io::{self, Write},
+    process,
+};
+
+/// Writer that turns a closed pipe into the end of output.
+///
+/// After a write fails with `BrokenPipe`, like when piping into `head` and it has
+/// seen enough, everything written is quietly discarded. Other errors are passed on.
+pub struct PipeWriter<W> {
+    inner: W,
+    closed: bool,
+}
+
+impl<W: Write> PipeWriter<W> {
+    /// Wrap a writer.
+    pub fn new(inner: W) -> Self {
+        PipeWriter {
+            inner,
+            closed: false,
+        }
+    }
+
+    /// Whether the reader has gone away.
+    pub fn closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Note a broken pipe, passing other results through.
+    fn check<T>(&mut self, result: io::Result<T>, discarded: T) -> io::Result<T> {
+        match result {
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {
+                self.closed = true;
+                Ok(discarded)
+            }
+            other => other,
+        }
+    }
+}
+
+impl<W: Write> Write for PipeWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.closed {
+            return Ok(buf.len());
+        }
+        let result = self.inner.write(buf);
+        self.check(result, buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.closed {
+            return Ok(());
+        }
+        let result = self.inner.flush();
+        self.check(result, ())
+    }
+}
+
+/// What ends each link printed with `--print`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Terminator {
+    Newline,
+    /// A NUL byte, with `--print0`, for `xargs -0`.
+    Nul,
+}
+
+impl Terminator {
+    fn byte(self) -> u8 {
+        match self {
+            Terminator::Newline => b'\n',
+            Terminator::Nul => b'\0',
+        }
+    }
+}
+
+/// Write output ended by the terminator, followed by a flush.
+pub fn write_terminated<W: Write>(
+    out: &mut PipeWriter<W>,
+    text: &str,
+    terminator: Terminator,
+) -> io::Result<()> {
+    let mut record = Vec::with_capacity(text.len() + 1);
+    record.extend_from_slice(text.as_bytes());
+    record.push(terminator.byte());
+    out.write_all(&record)?;
+    out.flush()
+}
+
+/// Print a line of output to stdout.
+///
+/// If nothing is reading stdout anymore, there's no point going on, so the process
+/// exits successfully. Other errors writing are reported and exit with an error.
+pub fn print(text: &str) {
+    print_link(text, Terminator::Newline);
+}
+
+/// Print text to stdout without ending the line, like a question for the user.
+pub fn print_partial(text: &str) {
+    let stdout = io::stdout();
+    let mut out = PipeWriter::new(stdout.lock());
+    let result = out.write_all(text.as_bytes()).and_then(|()| out.flush());
+    finish(result, out.closed());
+}
+
+/// Print a link (or path) for `--print` to stdout, ended by the terminator.
+pub fn print_link(text: &str, terminator: Terminator) {
+    let stdout = io::stdout();
+    let mut out = PipeWriter::new(stdout.lock());
+    finish(write_terminated(&mut out, text, terminator), out.closed());
+}
+
+/// Exit appropriately after writing output failed or found the pipe closed.
+pub fn finish(result: io::Result<()>, closed: bool) {
+    match result {
+        Ok(()) if closed => process::exit(0),
+        Ok(()) => (),
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => process::exit(0),
+        Err(e) => {
+            eprintln!("Could not write output: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_terminated, PipeWriter, Terminator};
+    use std::io::{self, Write};
+
+    /// Writer that accepts a number of writes, then fails them all.
+    struct Failing {
+        written: Vec<u8>,
+        allowed: usize,
+        kind: io::ErrorKind,
+    }
+
+    impl Write for Failing {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.allowed == 0 {
+                return Err(io::Error::new(self.kind, "failed"));
+            }
+            self.allowed -= 1;
+            self.written.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn failing(allowed: usize, kind: io::ErrorKind) -> Failing {
+        Failing {
+            written: Vec::new(),
+            allowed,
+            kind,
+        }
+    }
+
+    fn write_line<W: Write>(out: &mut PipeWriter<W>, text: &str) -> io::Result<()> {
+        write_terminated(out, text, Terminator::Newline)
+    }
+
+    #[test]
+    fn test_write_line() {
+        let mut out = PipeWriter::new(Vec::new());
+        write_line(&mut out, "https://docs.rs/serde").unwrap();
+        assert!(!out.closed());
+        assert_eq!(out.inner, b"https://docs.rs/serde\n");
+    }
+
+    #[test]
+    fn test_write_nul_terminated() {
+        let mut out = PipeWriter::new(Vec::new());
+        write_terminated(&mut out, "https://docs.rs/serde", Terminator::Nul).unwrap();
+        write_terminated(&mut out, "/home/me/my dir", Terminator::Nul).unwrap();
+        assert_eq!(out.inner, b"https://docs.rs/serde\0/home/me/my dir\0");
+    }
+
+    #[test]
+    fn test_broken_pipe_stops_output() {
+        let mut out = PipeWriter::new(failing(1, io::ErrorKind::BrokenPipe));
+        write_line(&mut out, "first").unwrap();
+        assert!(!out.closed());
+        write_line(&mut out, "second").unwrap();
+        assert!(out.closed());
+        write_line(&mut out, "third").unwrap();
+        assert_eq!(out.inner.written, b"first\n");
+    }
+
+    #[test]
+    fn test_other_errors_reported() {
+        let mut out = PipeWriter::new(failing(0, io::ErrorKind::PermissionDenied));
+        let err = write_line(&mut out, "first").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert!(!out.closed());
+    }
+}
@@ -0,0 +1,168 @@
+//! Showing long output through a pager, like `less`.
+
+use crate::environment::{self, Environment};
+use log::debug;
+use std::{
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+
+/// Pager used when `PAGER` isn't set.
+pub const DEFAULT_PAGER: &str = "less -FRX";
+
+/// Where long output goes.
+#[derive(Debug)]
+pub struct Pager {
+    /// Pager command, or `None` to always print directly.
+    command: Option<String>,
+    /// Number of lines that fit on the terminal.
+    height: Option<usize>,
+}
+
+impl Pager {
+    /// Page output longer than the terminal when `enabled`, with the `PAGER`
+    /// command if it's set. An empty `PAGER` turns paging off.
+    pub fn new(enabled: bool, environment: &Environment) -> Self {
+        let command = match environment.var(environment::PAGER) {
+            Some(p) if p.trim().is_empty() => None,
+            Some(p) => Some(p),
+            None => Some(DEFAULT_PAGER.to_owned()),
+        };
+        Pager {
+            command: command.filter(|_| enabled),
+            height: terminal_size().map(|(rows, _)| rows),
+        }
+    }
+
+    /// The pager command, if the text is too long to fit on the terminal.
+    fn command_for(&self, text: &str) -> Option<&str> {
+        let height = self.height?;
+        if text.lines().count() < height {
+            return None;
+        }
+        self.command.as_deref()
+    }
+
+    /// Show the text, through the pager if it's too long for the terminal.
+    ///
+    /// If the pager can't be started, the text is printed directly. Quitting the
+    /// pager early isn't an error; a closed stdout is reported as `BrokenPipe`.
+    pub fn show(&self, text: &str) -> io::Result<()> {
+        if let Some(command) = self.command_for(text) {
+            match run_pager(command, text) {
+                Ok(()) => return Ok(()),
+                Err(e) => debug!("Could not run the pager {}: {}", command, e),
+            }
+        }
+        let mut stdout = io::stdout();
+        writeln!(stdout, "{}", text)?;
+        stdout.flush()
+    }
+}
+
+/// Pipe the text into the pager command and wait for it to exit.
+///
+/// The command is split on whitespace and run directly, not through a shell.
+pub fn run_pager(command: &str, text: &str) -> io::Result<()> {
+    let mut words = command.split_whitespace();
+    let program = words
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty pager command"))?;
+    let mut child = Command::new(program)
+        .args(words)
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        match writeln!(stdin, "{}", text) {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => return Err(e),
+            // the pager was quit before reading everything
+            _ => (),
+        }
+    }
+    child.wait()?;
+    Ok(())
+}
+
+/// Rows and columns of the terminal stdout is connected to, if it is one.
+#[cfg(unix)]
+fn terminal_size() -> Option<(usize, usize)> {
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+    if result == 0 && size.ws_row > 0 && size.ws_col > 0 {
+        Some((usize::from(size.ws_row), usize::from(size.ws_col)))
+    } else {
+        None
+    }
+}
+
+/// Rows and columns of the terminal stdout is connected to, if it is one.
+#[cfg(not(unix))]
+fn terminal_size() -> Option<(usize, usize)> {
+    None
+}
+
+/// Width of the terminal stdout is connected to, if it is one.
+pub fn terminal_width() -> Option<usize> {
+    terminal_size().map(|(_, columns)| columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run_pager, Pager, DEFAULT_PAGER};
+    use crate::environment::Environment;
+    use std::io::ErrorKind;
+
+    fn pager(command: Option<&str>, height: Option<usize>) -> Pager {
+        Pager {
+            command: command.map(str::to_owned),
+            height,
+        }
+    }
+
+    #[test]
+    fn test_command_for() {
+        let long = "line\n".repeat(30);
+        assert_eq!(
+            pager(Some("less"), Some(24)).command_for(&long),
+            Some("less")
+        );
+        assert_eq!(pager(Some("less"), Some(24)).command_for("short"), None);
+        assert_eq!(pager(Some("less"), None).command_for(&long), None);
+        assert_eq!(pager(None, Some(24)).command_for(&long), None);
+        assert_eq!(pager(None, None).command_for(&long), None);
+    }
+
+    #[test]
+    fn test_new() {
+        let disabled = Pager::new(false, &Environment::new(false));
+        assert_eq!(disabled.command, None);
+        let enabled = Pager::new(true, &Environment::new(false));
+        assert_eq!(enabled.command.as_deref(), Some(DEFAULT_PAGER));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_run_pager() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("paged.txt");
+        let command = format!("cp /dev/stdin {}", path.display());
+        run_pager(&command, "one\ntwo").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "one\ntwo\n");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_run_pager_quit_early() {
+        run_pager("true", &"line\n".repeat(100_000)).unwrap();
+    }
+
+    #[test]
+    fn test_run_pager_missing() {
+        let err = run_pager("cargo-nav-no-such-pager -R", "text").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert_eq!(
+            run_pager("  ", "text").unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! Interactive prompts, answered automatically with `--assume-yes`.
+
+use crate::output;
+use anyhow::Result;
+use log::debug;
+use std::io::BufRead;
+
+/// Source of answers to questions for the user.
+pub struct Prompt<R> {
+    input: R,
+    /// Whether the user can be asked, with a terminal on stdin and stdout.
+    interactive: bool,
+    /// Whether to take the default answer to everything without asking.
+    assume_yes: bool,
+}
+
+impl<R: BufRead> Prompt<R> {
+    /// Prompt reading answers from `input`.
+    pub fn new(input: R, interactive: bool, assume_yes: bool) -> Self {
+        Prompt {
+            input,
+            interactive,
+            assume_yes,
+        }
+    }
+
+    /// Whether questions get answers, from the user or automatically.
+    pub fn available(&self) -> bool {
+        self.interactive || self.assume_yes
+    }
+
+    /// Ask a question, returning the trimmed answer.
+    ///
+    /// With `--assume-yes` the `default` answer is taken without asking. When the
+    /// user can't be asked, there's no answer.
+    pub fn ask(&mut self, question: &str, default: &str) -> Result<Option<String>> {
+        if self.assume_yes {
+            debug!("Answering '{}' to: {}", default, question.trim());
+            return Ok(Some(default.to_owned()));
+        }
+        if !self.interactive {
+            return Ok(None);
+        }
+        output::print_partial(question);
+        let mut line = String::new();
+        self.input.read_line(&mut line)?;
+        Ok(Some(line.trim().to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Prompt;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_ask_interactive() {
+        let mut prompt = Prompt::new(Cursor::new("  repo \n"), true, false);
+        assert!(prompt.available());
+        assert_eq!(
+            prompt.ask("Destination: ", "crate").unwrap().as_deref(),
+            Some("repo")
+        );
+    }
+
+    #[test]
+    fn test_ask_assume_yes() {
+        for &interactive in &[true, false] {
+            let mut prompt = Prompt::new(Cursor::new("repo\n"), interactive, true);
+            assert!(prompt.available());
+            assert_eq!(
+                prompt.ask("Destination: ", "crate").unwrap().as_deref(),
+                Some("crate")
+            );
+        }
+    }
+
+    #[test]
+    fn test_ask_not_interactive() {
+        let mut prompt = Prompt::new(Cursor::new("repo\n"), false, false);
+        assert!(!prompt.available());
+        assert_eq!(prompt.ask("Destination: ", "crate").unwrap(), None);
+    }
+}
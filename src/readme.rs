@@ -0,0 +1,127 @@
+//! Guessing a crate's homepage from the links in its README.
+
+use regex::Regex;
+
+/// Markdown links, allowing an image (like a badge) as the link text.
+const MARKDOWN_LINK_PATTERN: &str = r#"\[((?:[^\[\]]|\[[^\]]*\])*)\]\((https?://[^)\s]+)\)"#;
+/// HTML links with plain text.
+const HTML_LINK_PATTERN: &str = r#"<a\s[^>]*href="(https?://[^"]+)"[^>]*>([^<]*)</a>"#;
+/// Lines labeling a bare link, like `Homepage: https://example.com`.
+const LABELED_LINK_PATTERN: &str =
+    r#"(?im)^[\s*_>#-]*(?:homepage|website)[\s*_]*:[\s*_]*<?(https?://[^\s)>\]]+)"#;
+
+/// Words in link text that mark a link as the homepage.
+const HOMEPAGE_WORDS: &[&str] = &["homepage", "home page", "website", "web site"];
+/// Words in link text that mark a link as documentation, used when there's no
+/// homepage link.
+const DOCUMENTATION_WORDS: &[&str] = &["documentation", "docs", "book", "guide"];
+
+/// Links in the README in the order they appear, with their text.
+fn links(readme: &str) -> Vec<(String, String)> {
+    let markdown = Regex::new(MARKDOWN_LINK_PATTERN).expect("valid Markdown link pattern");
+    let html = Regex::new(HTML_LINK_PATTERN).expect("valid HTML link pattern");
+    let mut found = markdown
+        .captures_iter(readme)
+        .map(|c| (c.get(0).unwrap().start(), c[1].to_owned(), c[2].to_owned()))
+        .chain(
+            html.captures_iter(readme)
+                .map(|c| (c.get(0).unwrap().start(), c[2].to_owned(), c[1].to_owned())),
+        )
+        .collect::<Vec<_>>();
+    found.sort_by_key(|(start, _, _)| *start);
+    found
+        .into_iter()
+        .map(|(_, text, url)| (text.to_lowercase(), url))
+        .collect()
+}
+
+/// The first link whose text has one of the words.
+fn link_with_words(links: &[(String, String)], words: &[&str]) -> Option<String> {
+    links
+        .iter()
+        .find(|(text, _)| words.iter().any(|word| text.contains(word)))
+        .map(|(_, url)| url.clone())
+}
+
+/// The README's homepage link, or else its documentation link.
+///
+/// This is a guess: a link labeled "Homepage" or "Website", in a line like
+/// `Homepage: <url>` or as the text of a link, is taken first, then a link about
+/// documentation, like a docs badge.
+pub fn homepage_link(readme: &str) -> Option<String> {
+    let labeled = Regex::new(LABELED_LINK_PATTERN).expect("valid labeled link pattern");
+    if let Some(c) = labeled.captures(readme) {
+        // punctuation ending a sentence after a bare link isn't part of it
+        return Some(
+            c[1].trim_end_matches(['.', ',', ';', ':', '!', '?'])
+                .to_owned(),
+        );
+    }
+    let links = links(readme);
+    link_with_words(&links, HOMEPAGE_WORDS).or_else(|| link_with_words(&links, DOCUMENTATION_WORDS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::homepage_link;
+
+    #[test]
+    fn test_labeled_line() {
+        let readme = "# tool\n\nA tool.\n\n**Homepage**: <https://tool.example.com>\n";
+        assert_eq!(
+            homepage_link(readme).as_deref(),
+            Some("https://tool.example.com")
+        );
+        assert_eq!(
+            homepage_link("Website: https://tool.example.com/.").as_deref(),
+            Some("https://tool.example.com/")
+        );
+    }
+
+    #[test]
+    fn test_link_text() {
+        let readme = r#"
+# tool
+
+[![docs](https://img.shields.io/docsrs/tool)](https://docs.rs/tool)
+
+See the [project website](https://tool.example.com) for more.
+"#;
+        assert_eq!(
+            homepage_link(readme).as_deref(),
+            Some("https://tool.example.com")
+        );
+    }
+
+    #[test]
+    fn test_html_link() {
+        let readme = r#"<p align="center"><a href="https://tool.example.com">Website</a></p>"#;
+        assert_eq!(
+            homepage_link(readme).as_deref(),
+            Some("https://tool.example.com")
+        );
+    }
+
+    #[test]
+    fn test_documentation_fallback() {
+        let readme = r#"
+[![Crates.io](https://img.shields.io/crates/v/tool.svg)](https://crates.io/crates/tool)
+[![Documentation](https://docs.rs/tool/badge.svg)](https://docs.rs/tool)
+
+Read [the book](https://tool.example.com/book/) to get started.
+"#;
+        assert_eq!(
+            homepage_link(readme).as_deref(),
+            Some("https://docs.rs/tool")
+        );
+    }
+
+    #[test]
+    fn test_none() {
+        assert_eq!(
+            homepage_link("# tool\n\nSee [the changelog](https://example.com/CHANGELOG.md)."),
+            None
+        );
+        assert_eq!(homepage_link(""), None);
+    }
+}
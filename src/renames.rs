@@ -0,0 +1,128 @@
+//! Repositories found to have moved, remembered so later runs use the new location.
+//!
+//! When a forge permanently redirects a crate's repository to a different path,
+//! like after a GitHub rename or transfer, the new URL is kept in a JSON file in
+//! the user's data directory, keyed by the old URL.
+
+use crate::forge;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Known repository moves, from the old URL to the new one.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Renames {
+    repositories: BTreeMap<String, String>,
+}
+
+impl Renames {
+    /// Load the renames file, starting fresh if there isn't one.
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .map_err(|e| anyhow!("Invalid renames file {}: {}", path.display(), e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Renames::default()),
+            Err(e) => Err(anyhow!("Could not read {}: {}", path.display(), e)),
+        }
+    }
+
+    /// Write the renames file, creating its directory if needed.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Where the repository is known to have moved to.
+    pub fn get(&self, repository: &str) -> Option<&str> {
+        self.repositories.get(&key(repository)).map(String::as_str)
+    }
+
+    /// Remember that the repository moved.
+    pub fn record(&mut self, old: &str, new: &str) {
+        self.repositories
+            .insert(key(old), forge::normalize_repo_url(new));
+    }
+}
+
+/// Repository URLs are compared normalized and ignoring case, as forges do.
+fn key(repository: &str) -> String {
+    forge::normalize_repo_url(repository).to_lowercase()
+}
+
+/// Whether a redirect from one repository URL to another is a move to a different
+/// repository, rather than the same one spelled differently.
+pub fn is_move(old: &str, new: &str) -> bool {
+    let path = |url: &str| {
+        let url = key(url);
+        let url = url.split(['?', '#']).next().unwrap_or_default();
+        url.split_once("://")
+            .map_or(url, |(_, rest)| rest)
+            .to_owned()
+    };
+    path(old) != path(new)
+}
+
+/// Location of the renames file.
+pub fn renames_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("cargo-nav").join("renames.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_move, Renames};
+
+    #[test]
+    fn test_record_get() {
+        let mut renames = Renames::default();
+        renames.record(
+            "https://github.com/old-org/name.git",
+            "https://github.com/new-org/name/",
+        );
+        assert_eq!(
+            renames.get("https://github.com/Old-Org/name"),
+            Some("https://github.com/new-org/name")
+        );
+        assert_eq!(renames.get("https://github.com/new-org/name"), None);
+    }
+
+    #[test]
+    fn test_is_move() {
+        assert!(is_move(
+            "https://github.com/old-org/name",
+            "https://github.com/new-org/name"
+        ));
+        assert!(is_move(
+            "https://github.com/org/old",
+            "https://github.com/org/new"
+        ));
+        assert!(!is_move(
+            "http://github.com/org/name",
+            "https://github.com/org/name/"
+        ));
+        assert!(!is_move(
+            "https://github.com/Org/Name.git",
+            "https://github.com/org/name"
+        ));
+    }
+
+    #[test]
+    fn test_save_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("renames.json");
+        assert!(Renames::load(&path).unwrap().repositories.is_empty());
+        let mut renames = Renames::default();
+        renames.record("https://github.com/a/b", "https://github.com/c/b");
+        renames.save(&path).unwrap();
+        assert_eq!(
+            Renames::load(&path).unwrap().get("https://github.com/a/b"),
+            Some("https://github.com/c/b")
+        );
+    }
+}
@@ -0,0 +1,56 @@
+//! External resolvers: commands that look up crate info instead of crates.io.
+
+use anyhow::{anyhow, Result};
+use log::debug;
+use std::process::Command;
+
+/// Run the resolver command for the crate, returning what it printed.
+///
+/// The crate name is passed as the last argument. Like the pre-open hook, the
+/// command is split on whitespace and run directly, not through a shell.
+pub fn run(command: &str, crate_name: &str) -> Result<String> {
+    let mut words = command.split_whitespace();
+    let program = words
+        .next()
+        .ok_or_else(|| anyhow!("The resolver command is empty"))?;
+    debug!("Running resolver {} for {}", program, crate_name);
+    let output = Command::new(program)
+        .args(words)
+        .arg(crate_name)
+        .output()
+        .map_err(|e| anyhow!("Could not run the resolver {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "The resolver {} failed ({}): {}",
+            program,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    String::from_utf8(output.stdout)
+        .map_err(|_| anyhow!("The resolver {} printed invalid UTF-8", program))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run;
+
+    #[test]
+    #[cfg(unix)]
+    fn test_run() {
+        assert_eq!(run("echo --name", "serde").unwrap(), "--name serde\n");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_failure() {
+        let err = run("false", "serde").unwrap_err();
+        assert!(err.to_string().contains("The resolver false failed"));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(run("  ", "serde").is_err());
+        assert!(run("cargo-nav-no-such-resolver", "serde").is_err());
+    }
+}
@@ -0,0 +1,190 @@
+//! A minimal HTTP server for editor integrations, answering link lookups as JSON.
+//!
+//! `GET /crate/<name>?dest=<destination>` responds with the link that would be
+//! opened. Requests are handled one at a time.
+
+use crate::urls;
+use log::{debug, info};
+use serde_json::{json, Value};
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    time::Duration,
+};
+use url::form_urlencoded;
+
+/// How long to wait on a client that's slow to send its request, since one that
+/// never does would otherwise hold up every request after it.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Looks up a crate's link: given the crate name and destination, if one was
+/// given, returns the crate's canonical name, the destination's name, and the link.
+pub type Resolver<'a> = dyn Fn(&str, Option<&str>) -> anyhow::Result<(String, String, String)> + 'a;
+
+/// Status code and JSON body answering the request for the path (with its query).
+pub fn respond(target: &str, resolve: &Resolver) -> (u16, Value) {
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path, query),
+        None => (target, ""),
+    };
+    let name = match path.strip_prefix("/crate/") {
+        Some(name) if !name.is_empty() && !name.contains('/') => urls::decode(name),
+        _ => {
+            return (
+                404,
+                json!({ "error": "Not found; use /crate/<name>?dest=<destination>" }),
+            )
+        }
+    };
+    let destination = form_urlencoded::parse(query.as_bytes())
+        .find(|(key, _)| key == "dest")
+        .map(|(_, value)| value)
+        .filter(|value| !value.is_empty());
+    match resolve(&name, destination.as_deref()) {
+        Ok((name, destination, url)) => (
+            200,
+            json!({ "name": name, "destination": destination, "url": url }),
+        ),
+        Err(e) => (422, json!({ "name": name, "error": e.to_string() })),
+    }
+}
+
+/// Reason phrase for the status codes used.
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        422 => "Unprocessable Entity",
+        _ => "Bad Request",
+    }
+}
+
+/// Read one request from the connection and write the response.
+pub fn handle_connection(stream: TcpStream, resolve: &Resolver) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // the headers aren't needed, but are read so the client isn't cut off
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && !header.trim().is_empty() {
+        header.clear();
+    }
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) => respond(target, resolve),
+        (Some(_), Some(_)) => (405, json!({ "error": "Only GET is supported" })),
+        _ => (400, json!({ "error": "Malformed request" })),
+    };
+    debug!("{} -> {}", request_line.trim(), status);
+    let body = body.to_string();
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason(status),
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// Answer requests on the listener until the process is stopped.
+pub fn serve(listener: TcpListener, resolve: &Resolver) -> io::Result<()> {
+    info!("Listening on http://{}", listener.local_addr()?);
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let handled = stream
+                    .set_read_timeout(Some(READ_TIMEOUT))
+                    .and_then(|()| handle_connection(stream, resolve));
+                if let Err(e) = handled {
+                    debug!("Error handling a request: {}", e);
+                }
+            }
+            Err(e) => debug!("Error accepting a connection: {}", e),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{handle_connection, respond};
+    use anyhow::anyhow;
+    use serde_json::json;
+    use std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
+        thread,
+    };
+
+    fn resolve(name: &str, destination: Option<&str>) -> anyhow::Result<(String, String, String)> {
+        match destination.unwrap_or("crate") {
+            "d" => Ok((
+                name.to_owned(),
+                "documentation".to_owned(),
+                format!("https://docs.rs/{}", name),
+            )),
+            "crate" => Ok((
+                name.to_owned(),
+                "crate".to_owned(),
+                format!("https://crates.io/crates/{}", name),
+            )),
+            other => Err(anyhow!("Unknown destination '{}'", other)),
+        }
+    }
+
+    #[test]
+    fn test_respond() {
+        assert_eq!(
+            respond("/crate/serde?dest=d", &resolve),
+            (
+                200,
+                json!({"name": "serde", "destination": "documentation", "url": "https://docs.rs/serde"})
+            )
+        );
+        assert_eq!(respond("/crate/serde", &resolve).1["destination"], "crate");
+        assert_eq!(
+            respond("/crate/serde?dest=", &resolve).1["destination"],
+            "crate"
+        );
+        let (status, body) = respond("/crate/serde?dest=nope", &resolve);
+        assert_eq!(status, 422);
+        assert_eq!(body["error"], "Unknown destination 'nope'");
+        assert_eq!(respond("/", &resolve).0, 404);
+        assert_eq!(respond("/crate/", &resolve).0, 404);
+        assert_eq!(respond("/crate/a/b", &resolve).0, 404);
+        assert_eq!(
+            respond("/crate/serde%5Fjson?dest=%64", &resolve).1,
+            json!({"name": "serde_json", "destination": "documentation", "url": "https://docs.rs/serde_json"})
+        );
+        assert_eq!(respond("/crate/a%2Fb", &resolve).1["name"], "a/b");
+    }
+
+    #[test]
+    fn test_handle_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream
+                .write_all(b"GET /crate/serde?dest=d HTTP/1.1\r\nHost: localhost\r\n\r\n")
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        });
+        let (stream, _) = listener.accept().unwrap();
+        handle_connection(stream, &resolve).unwrap();
+        let response = client.join().unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(head.contains("Content-Type: application/json"));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(body).unwrap(),
+            json!({"name": "serde", "destination": "documentation", "url": "https://docs.rs/serde"})
+        );
+    }
+}
@@ -0,0 +1,103 @@
+//! Session files: a JSON Lines log of opened links that can be shared and replayed.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// One opened link.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct SessionRecord {
+    pub crate_name: String,
+    pub destination: String,
+    pub url: String,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+impl SessionRecord {
+    /// Record of a link opened now.
+    pub fn new(crate_name: &str, destination: &str, url: &str) -> Self {
+        SessionRecord {
+            crate_name: crate_name.to_owned(),
+            destination: destination.to_owned(),
+            url: url.to_owned(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// Add a record to the end of the session file, creating it if needed.
+pub fn append(path: &Path, record: &SessionRecord) -> Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
+
+/// Read every record from a session file.
+pub fn read(path: &Path) -> Result<Vec<SessionRecord>> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| anyhow!("Could not read {}: {}", path.display(), e))?;
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| {
+                anyhow!(
+                    "Invalid record on line {} of {}: {}",
+                    i + 1,
+                    path.display(),
+                    e
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{append, read, SessionRecord};
+    use std::fs;
+
+    #[test]
+    fn test_append_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let first = SessionRecord::new("serde", "documentation", "https://docs.rs/serde");
+        let second = SessionRecord::new("tokio", "crate", "https://crates.io/crates/tokio");
+        append(&path, &first).unwrap();
+        append(&path, &second).unwrap();
+        let records = read(&path).unwrap();
+        assert_eq!(records, vec![first, second]);
+    }
+
+    #[test]
+    fn test_read_skips_blank_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        fs::write(
+            &path,
+            "\n{\"crate_name\":\"a\",\"destination\":\"crate\",\"url\":\"u\",\"timestamp\":1}\n\n",
+        )
+        .unwrap();
+        let records = read(&path).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].url, "u");
+    }
+
+    #[test]
+    fn test_read_invalid() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        fs::write(&path, "not json\n").unwrap();
+        let err = read(&path).unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+    }
+}
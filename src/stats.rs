@@ -0,0 +1,133 @@
+//! Local, opt-in tally of which crates and destinations are navigated to.
+//!
+//! Nothing here ever leaves the machine; counts are kept in a JSON file in the
+//! user's data directory.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Navigation counts, keyed by crate name and then destination.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Stats {
+    counts: BTreeMap<String, BTreeMap<String, u64>>,
+}
+
+impl Stats {
+    /// Load the stats file, starting fresh if there isn't one.
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .map_err(|e| anyhow!("Invalid stats file {}: {}", path.display(), e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Stats::default()),
+            Err(e) => Err(anyhow!("Could not read {}: {}", path.display(), e)),
+        }
+    }
+
+    /// Write the stats file, creating its directory if needed.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Count a navigation to the crate's destination.
+    pub fn increment(&mut self, crate_name: &str, destination: &str) {
+        *self
+            .counts
+            .entry(crate_name.to_owned())
+            .or_default()
+            .entry(destination.to_owned())
+            .or_insert(0) += 1;
+    }
+
+    /// The most navigated-to crates with their total counts, busiest first.
+    pub fn top(&self, limit: usize) -> Vec<(&str, u64)> {
+        let mut totals = self
+            .counts
+            .iter()
+            .map(|(name, destinations)| (name.as_str(), destinations.values().sum::<u64>()))
+            .collect::<Vec<_>>();
+        totals.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        totals.truncate(limit);
+        totals
+    }
+
+    /// Human-readable listing of the most navigated-to crates.
+    pub fn report(&self, limit: usize) -> String {
+        let top = self.top(limit);
+        if top.is_empty() {
+            return String::from("No navigation has been recorded yet");
+        }
+        top.iter()
+            .map(|(name, total)| {
+                let destinations = self.counts[*name]
+                    .iter()
+                    .map(|(d, c)| format!("{} {}", d, c))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{:>5}  {} ({})", total, name, destinations)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Location of the stats file.
+pub fn stats_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("cargo-nav").join("stats.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stats;
+
+    #[test]
+    fn test_increment() {
+        let mut stats = Stats::default();
+        stats.increment("serde", "documentation");
+        stats.increment("serde", "documentation");
+        stats.increment("serde", "repository");
+        assert_eq!(stats.counts["serde"]["documentation"], 2);
+        assert_eq!(stats.counts["serde"]["repository"], 1);
+    }
+
+    #[test]
+    fn test_top() {
+        let mut stats = Stats::default();
+        stats.increment("tokio", "crate");
+        stats.increment("serde", "documentation");
+        stats.increment("serde", "repository");
+        stats.increment("anyhow", "crate");
+        assert_eq!(stats.top(2), vec![("serde", 2), ("anyhow", 1)]);
+    }
+
+    #[test]
+    fn test_report() {
+        let mut stats = Stats::default();
+        assert_eq!(stats.report(10), "No navigation has been recorded yet");
+        stats.increment("serde", "documentation");
+        stats.increment("serde", "repository");
+        assert_eq!(
+            stats.report(10),
+            "    2  serde (documentation 1, repository 1)"
+        );
+    }
+
+    #[test]
+    fn test_save_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("stats.json");
+        let mut stats = Stats::default();
+        stats.increment("serde", "crate");
+        stats.save(&path).unwrap();
+        let loaded = Stats::load(&path).unwrap();
+        assert_eq!(loaded.top(10), vec![("serde", 1)]);
+    }
+}
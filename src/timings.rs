@@ -0,0 +1,109 @@
+//! Time spent in each phase of a run, shown with the hidden `--timings` flag.
+
+use std::{
+    cell::RefCell,
+    time::{Duration, Instant},
+};
+
+/// Phases of a run and how long each took, reported to stderr when dropped.
+pub struct Timings {
+    enabled: bool,
+    start: Instant,
+    /// Each finished phase, with when it finished.
+    phases: RefCell<Vec<(&'static str, Instant)>>,
+}
+
+impl Timings {
+    /// Start timing from `start`, reporting only if `enabled`.
+    pub fn new(enabled: bool, start: Instant) -> Self {
+        Timings {
+            enabled,
+            start,
+            phases: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Note that a phase, started when the previous one finished, is done.
+    pub fn finish(&self, phase: &'static str) {
+        self.phases.borrow_mut().push((phase, Instant::now()));
+    }
+
+    /// Each phase with its duration, in order.
+    pub fn durations(&self) -> Vec<(&'static str, Duration)> {
+        let mut previous = self.start;
+        self.phases
+            .borrow()
+            .iter()
+            .map(|(phase, finished)| {
+                let duration = finished.saturating_duration_since(previous);
+                previous = *finished;
+                (*phase, duration)
+            })
+            .collect()
+    }
+
+    /// Breakdown of the phases and the total, one per line.
+    pub fn report(&self) -> String {
+        let durations = self.durations();
+        let total = durations.iter().map(|(_, d)| *d).sum::<Duration>();
+        durations
+            .iter()
+            .chain(std::iter::once(&("total", total)))
+            .map(|(phase, duration)| format!("{:<10} {:>9.3} ms", phase, millis(*duration)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Drop for Timings {
+    fn drop(&mut self) {
+        if self.enabled {
+            eprintln!("{}", self.report());
+        }
+    }
+}
+
+/// Duration in fractional milliseconds.
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Timings;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_durations() {
+        let start = Instant::now();
+        let timings = Timings::new(false, start);
+        timings.phases.borrow_mut().extend(vec![
+            ("config", start + Duration::from_millis(2)),
+            ("network", start + Duration::from_millis(12)),
+        ]);
+        assert_eq!(
+            timings.durations(),
+            vec![
+                ("config", Duration::from_millis(2)),
+                ("network", Duration::from_millis(10)),
+            ]
+        );
+        assert_eq!(
+            timings.report(),
+            "config         2.000 ms\nnetwork       10.000 ms\ntotal         12.000 ms"
+        );
+    }
+
+    #[test]
+    fn test_finish() {
+        let timings = Timings::new(false, Instant::now());
+        timings.finish("arg parse");
+        timings.finish("config");
+        let phases = timings
+            .durations()
+            .into_iter()
+            .map(|(p, _)| p)
+            .collect::<Vec<_>>();
+        assert_eq!(phases, vec!["arg parse", "config"]);
+    }
+}
@@ -0,0 +1,49 @@
+//! Step-by-step record of how a link was resolved, for `cargo nav resolve`.
+
+use std::fmt;
+
+/// Ordered list of resolution steps and their outcomes.
+#[derive(Debug, Default)]
+pub struct Trace {
+    steps: Vec<(String, String)>,
+}
+
+impl Trace {
+    /// Record a step.
+    pub fn step(&mut self, label: &str, detail: impl fmt::Display) {
+        self.steps.push((label.to_owned(), detail.to_string()));
+    }
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lines = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(i, (label, detail))| format!("{}. {}: {}", i + 1, label, detail))
+            .collect::<Vec<_>>();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Trace;
+
+    #[test]
+    fn test_display() {
+        let mut trace = Trace::default();
+        trace.step("crate name", "serde");
+        trace.step("final URL", "https://docs.rs/serde");
+        assert_eq!(
+            trace.to_string(),
+            "1. crate name: serde\n2. final URL: https://docs.rs/serde"
+        );
+    }
+
+    #[test]
+    fn test_display_empty() {
+        assert_eq!(Trace::default().to_string(), "");
+    }
+}
@@ -0,0 +1,152 @@
+//! Building URLs from crate names, versions, logins, and other input.
+//!
+//! Everything put into a URL is percent-encoded for where it goes, so spaces,
+//! `?`, `#`, `%`, or non-ASCII text can't change the URL's structure.
+
+use url::{form_urlencoded, Url};
+
+/// Percent-encode text as a single path segment, including any `/` in it.
+pub fn segment(text: &str) -> String {
+    // dot segments would be dropped or resolved, not kept as a name
+    match text {
+        "." => return String::from("%2E"),
+        ".." => return String::from("%2E%2E"),
+        _ => (),
+    }
+    let mut url = Url::parse("http://localhost/").expect("valid URL");
+    url.path_segments_mut()
+        .expect("URL with a path")
+        .pop_if_empty()
+        .push(text);
+    url.path()[1..].to_owned()
+}
+
+/// Append path segments to a base URL, each percent-encoded.
+pub fn path(base: &str, segments: &[&str]) -> String {
+    let mut url = base.trim_end_matches('/').to_owned();
+    for text in segments {
+        url.push('/');
+        url.push_str(&segment(text));
+    }
+    url
+}
+
+/// Append query parameters to a URL, form-encoded.
+pub fn query(base: &str, pairs: &[(&str, &str)]) -> String {
+    let query = form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(pairs)
+        .finish();
+    let separator = if base.contains('?') { '&' } else { '?' };
+    format!("{}{}{}", base, separator, query)
+}
+
+/// A URL fragment for the text, starting with `#`.
+pub fn fragment(text: &str) -> String {
+    format!("#{}", segment(text))
+}
+
+/// Text that would break a URL if put in as is.
+#[cfg(test)]
+pub const HOSTILE: &[&str] = &[
+    "two words",
+    "ünïcødé",
+    "100%",
+    "what?x=1",
+    "a#b",
+    "a/b",
+    "a&b=c",
+    "",
+];
+
+/// Decode a percent-encoded path segment. Anything that isn't a valid escape is
+/// kept as it is.
+pub fn decode(text: &str) -> String {
+    let mut bytes = Vec::new();
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = tail
+            .get(..2)
+            .filter(|_| byte == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, fragment, path, query, segment, HOSTILE};
+    use url::Url;
+
+    #[test]
+    fn test_segment() {
+        assert_eq!(segment("serde_json"), "serde_json");
+        assert_eq!(segment("two words"), "two%20words");
+        assert_eq!(segment("ü"), "%C3%BC");
+        assert_eq!(segment("100%"), "100%25");
+        assert_eq!(segment("what?x"), "what%3Fx");
+        assert_eq!(segment("a#b"), "a%23b");
+        assert_eq!(segment("a/b"), "a%2Fb");
+        assert_eq!(segment(".."), "%2E%2E");
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode("serde%5Fjson"), "serde_json");
+        assert_eq!(decode("two%20words"), "two words");
+        assert_eq!(decode("100%"), "100%");
+        assert_eq!(decode("%zz%4"), "%zz%4");
+        assert_eq!(decode("%FF"), "\u{FFFD}");
+    }
+
+    #[test]
+    fn test_path_round_trip() {
+        for text in HOSTILE {
+            let url = path("https://crates.io/", &["crates", text]);
+            let parsed = Url::parse(&url).unwrap();
+            assert_eq!(parsed.query(), None, "{}", text);
+            assert_eq!(parsed.fragment(), None, "{}", text);
+            let segments = parsed.path_segments().unwrap().collect::<Vec<_>>();
+            assert_eq!(segments.len(), 2, "{}", text);
+            assert_eq!(segments[0], "crates");
+            assert_eq!(decode(segments[1]), *text);
+        }
+    }
+
+    #[test]
+    fn test_query_round_trip() {
+        for text in HOSTILE {
+            let url = query("https://crates.io/search", &[("q", text)]);
+            let parsed = Url::parse(&url).unwrap();
+            assert_eq!(parsed.path(), "/search", "{}", text);
+            assert_eq!(parsed.fragment(), None, "{}", text);
+            let pairs = parsed.query_pairs().into_owned().collect::<Vec<_>>();
+            assert_eq!(pairs, vec![("q".to_owned(), text.to_string())]);
+        }
+        assert_eq!(
+            query("https://a.b/c?page=2", &[("per_page", "100")]),
+            "https://a.b/c?page=2&per_page=100"
+        );
+    }
+
+    #[test]
+    fn test_fragment() {
+        assert_eq!(fragment("full"), "#full");
+        for text in HOSTILE {
+            let url = format!("https://docs.rs/crate/a/latest/features{}", fragment(text));
+            let parsed = Url::parse(&url).unwrap();
+            assert_eq!(parsed.path(), "/crate/a/latest/features", "{}", text);
+            assert_eq!(parsed.query(), None, "{}", text);
+        }
+    }
+}
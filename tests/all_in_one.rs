@@ -0,0 +1,78 @@
+//! Opening the `--all-in-one` links page.
+
+use std::{
+    fs,
+    path::Path,
+    process::{Command, Output},
+};
+
+/// Run `--all-in-one` for a crate answered by a resolver, in `dir`, with an
+/// opener that writes the link it's given to `opened` there.
+fn run_all_in_one(dir: &Path, args: &[&str]) -> Output {
+    fs::write(
+        dir.join("resolver.sh"),
+        r#"echo '{"name": "serde", "documentation": "https://docs.rs/serde"}'"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.join("open.sh"),
+        r#"echo "$1" >> "$(dirname "$0")/opened""#,
+    )
+    .unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-nav"))
+        .arg("--resolver")
+        .arg(format!("sh {}", dir.join("resolver.sh").display()))
+        .arg("--opener-cmd")
+        .arg(format!("sh {} {{url}}", dir.join("open.sh").display()))
+        .args(args)
+        .args(["--all-in-one", "serde"])
+        .current_dir(dir)
+        .env("HOME", dir)
+        .env("TMPDIR", dir)
+        .env_remove("XDG_CONFIG_HOME")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    output
+}
+
+#[test]
+#[cfg(unix)]
+fn test_opens_page() {
+    let dir = tempfile::tempdir().unwrap();
+    run_all_in_one(dir.path(), &[]);
+    let opened = fs::read_to_string(dir.path().join("opened")).unwrap();
+    assert!(opened.starts_with("file://"), "{}", opened);
+    assert!(opened.trim_end().ends_with(".html"), "{}", opened);
+}
+
+#[test]
+#[cfg(unix)]
+fn test_records_page_to_session() {
+    let dir = tempfile::tempdir().unwrap();
+    let session = dir.path().join("session.jsonl");
+    run_all_in_one(dir.path(), &["--record-session", session.to_str().unwrap()]);
+    let recorded: serde_json::Value =
+        serde_json::from_str(fs::read_to_string(&session).unwrap().trim_end()).unwrap();
+    let opened = fs::read_to_string(dir.path().join("opened")).unwrap();
+    assert_eq!(recorded["crate_name"], "serde");
+    assert_eq!(recorded["destination"], "all-in-one");
+    assert_eq!(recorded["url"], opened.trim_end());
+}
+
+#[test]
+#[cfg(unix)]
+fn test_pre_open_cmd_can_decline_page() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run_all_in_one(dir.path(), &["--pre-open-cmd", "false"]);
+    assert!(!dir.path().join("opened").exists());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("the pre-open command declined it"),
+        "{}",
+        stdout
+    );
+
+    run_all_in_one(dir.path(), &["--pre-open-cmd", "true"]);
+    assert!(dir.path().join("opened").exists());
+}
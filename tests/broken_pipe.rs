@@ -0,0 +1,84 @@
+//! Output into a pipe that's closed before cargo-nav is done writing.
+
+use std::{
+    fs,
+    process::{Command, Stdio},
+};
+
+fn cargo_nav() -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_cargo-nav"));
+    command.stdin(Stdio::null()).stderr(Stdio::piped());
+    command
+}
+
+#[test]
+#[cfg(unix)]
+fn test_closed_before_writing() {
+    use std::os::unix::io::FromRawFd;
+
+    let mut fds = [0; 2];
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    unsafe { libc::close(fds[0]) };
+    let write_end = unsafe { Stdio::from_raw_fd(fds[1]) };
+    let output = cargo_nav()
+        .arg("--build-info")
+        .stdout(write_end)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    assert!(output.stderr.is_empty(), "{:?}", output);
+}
+
+/// Project with a path dependency, which cargo-nav only logs about, without
+/// needing the network.
+fn path_dependency_project() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("Cargo.toml"),
+        r#"[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+local-dep = { path = "local-dep" }
+"#,
+    )
+    .unwrap();
+    fs::create_dir(dir.path().join("local-dep")).unwrap();
+    dir
+}
+
+#[test]
+#[cfg(unix)]
+fn test_closed_before_logging() {
+    use std::os::unix::io::FromRawFd;
+
+    let dir = path_dependency_project();
+    let mut fds = [0; 2];
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    unsafe { libc::close(fds[0]) };
+    let write_end = unsafe { Stdio::from_raw_fd(fds[1]) };
+    let output = cargo_nav()
+        .args(["local-dep", "d"])
+        .current_dir(dir.path())
+        .env("HOME", dir.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .stdout(write_end)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    assert!(output.stderr.is_empty(), "{:?}", output);
+}
+
+#[test]
+fn test_closed_while_writing() {
+    let mut child = cargo_nav()
+        .arg("--build-info")
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    drop(child.stdout.take());
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    assert!(output.stderr.is_empty(), "{:?}", output);
+}
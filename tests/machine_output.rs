@@ -0,0 +1,88 @@
+//! Output meant for other programs, which notices mustn't get mixed into.
+
+use std::{
+    fs,
+    process::{Command, Output},
+};
+
+/// Run cargo-nav with a resolver that answers with the crate info JSON, and with
+/// `--offline`, instead of looking anything up on crates.io.
+fn run_resolved(info: &str, args: &[&str]) -> Output {
+    let dir = tempfile::tempdir().unwrap();
+    let resolver = dir.path().join("resolver.sh");
+    fs::write(&resolver, format!("echo '{}'", info)).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-nav"))
+        .arg("--resolver")
+        .arg(format!("sh {}", resolver.display()))
+        .arg("--offline")
+        .args(args)
+        .current_dir(dir.path())
+        .env("HOME", dir.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    output
+}
+
+/// Run cargo-nav for "Serde-JSON", answered with serde_json's info, so the
+/// crate's canonical name differs from the one asked for.
+fn run_renamed(args: &[&str]) -> Output {
+    let info = r#"{"name": "serde_json", "documentation": "https://docs.rs/serde_json"}"#;
+    run_resolved(info, &[args, &["Serde-JSON", "d"]].concat())
+}
+
+#[test]
+#[cfg(unix)]
+fn test_json_stdout_parses() {
+    let output = run_renamed(&["--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["url"], "https://docs.rs/serde_json");
+    assert!(json.get("download_trend").is_none(), "{}", json);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains(r#"published as "serde_json""#),
+        "{}",
+        stderr
+    );
+
+    let output = run_renamed(&["--print-json-field", "documentation"]);
+    assert_eq!(output.stdout, b"https://docs.rs/serde_json\n");
+}
+
+#[test]
+#[cfg(unix)]
+fn test_print_stdout_is_link() {
+    let output = run_renamed(&["--print"]);
+    assert_eq!(output.stdout, b"https://docs.rs/serde_json\n");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains(r#"published as "serde_json""#),
+        "{}",
+        stderr
+    );
+
+    let output = run_renamed(&["--print0"]);
+    assert_eq!(output.stdout, b"https://docs.rs/serde_json\0");
+}
+
+#[test]
+#[cfg(unix)]
+fn test_repository_caution_on_stderr() {
+    let info = r#"{"name": "tokyo", "documentation": "https://docs.rs/tokyo", "repository": "https://github.com/tokio-rs/tokio"}"#;
+    for args in [
+        &["--print", "tokyo", "d"][..],
+        &["--opener-cmd", "true", "tokyo", "d"],
+    ] {
+        let output = run_resolved(info, args);
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(
+            stderr.contains(r#"Caution: crate "tokyo" links to a repository for "tokio""#),
+            "{}",
+            stderr
+        );
+        assert!(!String::from_utf8(output.stdout)
+            .unwrap()
+            .contains("Caution"));
+    }
+}
@@ -0,0 +1,60 @@
+//! Startup work for runs that don't need the network, checked with `--timings`.
+
+use std::{fs, process::Command};
+
+#[test]
+fn test_path_dependency_skips_client() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("Cargo.toml"),
+        r#"[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+local-dep = { path = "local-dep" }
+"#,
+    )
+    .unwrap();
+    fs::create_dir(dir.path().join("local-dep")).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-nav"))
+        .args(["--timings", "--print", "local-dep", "r"])
+        .current_dir(dir.path())
+        .env("HOME", dir.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let local = dir.path().join("local-dep").canonicalize().unwrap();
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("{}\n", local.display())
+    );
+
+    // the note about the path dependency goes with the timings, not the path
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains(r#""local-dep" is a path dependency"#),
+        "{}",
+        stderr
+    );
+    let phases = stderr
+        .lines()
+        .filter(|l| l.ends_with(" ms"))
+        .map(|l| l.split("  ").next().unwrap().trim())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        phases,
+        vec!["arg parse", "config", "resolve", "total"],
+        "{}",
+        stderr
+    );
+    let total = stderr
+        .lines()
+        .find(|l| l.starts_with("total"))
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|ms| ms.parse::<f64>().ok())
+        .unwrap();
+    assert!(total < 5000.0, "took {} ms", total);
+}